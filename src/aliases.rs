@@ -0,0 +1,72 @@
+//! 物品和紫卡武器的别名, 储存在sled中. 全局一个tree, 每个群一个tree, key为别名, value为替换成的名字.
+//! 查找物品时先把名字开头的别名替换掉, 如`电男p头`在有`电男 => volt`时查找`voltp头`.
+
+use crate::db::ALIASES_DB;
+use crate::resolve::normalize;
+
+#[derive(Debug, Copy, Clone)]
+pub enum AliasScope {
+    Global,
+    Group(i64),
+}
+
+impl AliasScope {
+    fn tree(&self) -> sled::Result<sled::Tree> {
+        match self {
+            AliasScope::Global => ALIASES_DB.open_tree("global"),
+            AliasScope::Group(group) => ALIASES_DB.open_tree(format!("group_{}", group)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AliasScope::Global => "全局",
+            AliasScope::Group(_) => "本群",
+        }
+    }
+}
+
+pub fn add_alias(scope: AliasScope, alias: &str, target: &str) -> sled::Result<()> {
+    scope.tree()?.insert(normalize(alias), target.trim())?;
+    Ok(())
+}
+
+/// 返回别名是否存在
+pub fn remove_alias(scope: AliasScope, alias: &str) -> sled::Result<bool> {
+    Ok(scope.tree()?.remove(normalize(alias))?.is_some())
+}
+
+/// 所有别名和替换成的名字
+pub fn aliases(scope: AliasScope) -> sled::Result<Vec<(String, String)>> {
+    scope
+        .tree()?
+        .iter()
+        .map(|entry| {
+            entry.map(|(alias, target)| {
+                (
+                    String::from_utf8_lossy(alias.as_ref()).into_owned(),
+                    String::from_utf8_lossy(target.as_ref()).into_owned(),
+                )
+            })
+        })
+        .collect()
+}
+
+/// 替换名字开头最长的别名, 群别名优先于全局别名, 没有别名时原样返回(已`normalize`).
+pub fn apply_aliases(group: Option<i64>, name: &str) -> sled::Result<String> {
+    let name = normalize(name);
+    let scopes = group
+        .map(AliasScope::Group)
+        .into_iter()
+        .chain([AliasScope::Global]);
+    for scope in scopes {
+        let longest = aliases(scope)?
+            .into_iter()
+            .filter(|(alias, _)| name.starts_with(alias.as_str()))
+            .max_by_key(|(alias, _)| alias.len());
+        if let Some((alias, target)) = longest {
+            return Ok(format!("{}{}", normalize(&target), &name[alias.len()..]));
+        }
+    }
+    Ok(name)
+}
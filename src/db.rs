@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+
+use crate::resolve::{resolve_exact, NameIndex};
+use crate::wf_api::{
+    riven_attr_key, DucatRanking, ItemMeta, Platform, RivenAttribute, RivenWeapon, SetPart,
+    DUCAT_RANKINGS_TREE, ITEM_META_TREE, ITEM_SETS_TREE, PART_SETS_TREE, RIVEN_ATTRS_TREE,
+    RIVEN_ATTR_ALIASES_TREE, RIVEN_ATTR_NAMES_TREE, RIVEN_WEAPONS_TREE,
+};
+
+pub static ITEMS_DB: Lazy<Arc<sled::Db>> =
+    Lazy::new(|| Arc::new(sled::open("items_db").expect("ITEMS_DB open err")));
+pub static RIVENS_DB: Lazy<Arc<sled::Db>> =
+    Lazy::new(|| Arc::new(sled::open("rivens_db").expect("RIVENS_DB open err")));
+/// `ITEMS_DB`的名字索引, 用于`resolve`
+pub static ITEMS_INDEX: Lazy<NameIndex> = Lazy::new(|| NameIndex::new(ITEMS_DB.clone()));
+/// `RIVENS_DB`的名字索引, 用于`resolve`
+pub static RIVENS_INDEX: Lazy<NameIndex> = Lazy::new(|| NameIndex::new(RIVENS_DB.clone()));
+pub static SUBSCRIPTION_DB: Lazy<sled::Db> =
+    Lazy::new(|| sled::open("subscription_db").expect("SUBSCRIPTION_DB open err"));
+pub static HISTORY_DB: Lazy<sled::Db> =
+    Lazy::new(|| sled::open("history_db").expect("HISTORY_DB open err"));
+pub static SETTINGS_DB: Lazy<sled::Db> =
+    Lazy::new(|| sled::open("settings_db").expect("SETTINGS_DB open err"));
+pub static ALIASES_DB: Lazy<sled::Db> =
+    Lazy::new(|| sled::open("aliases_db").expect("ALIASES_DB open err"));
+pub static WATCHES_DB: Lazy<sled::Db> =
+    Lazy::new(|| sled::open("watches_db").expect("WATCHES_DB open err"));
+
+/// 用物品名(中文名, 不区分大小写和空格)查找warframe.market的`url_name`.
+pub fn item_url_name(name: &str) -> sled::Result<Option<String>> {
+    Ok(ITEMS_DB
+        .get(name.to_ascii_lowercase().replace(' ', "").as_bytes())?
+        .map(|url_name| String::from_utf8_lossy(url_name.as_ref()).into_owned()))
+}
+
+/// 用`resolve_exact`查找物品的`url_name`, 没有完全相同的名字或出错时为`None`.
+pub fn exact_url_name(name: &str) -> Option<String> {
+    match resolve_exact(&ITEMS_INDEX, name) {
+        Ok(url_name) => url_name,
+        Err(err) => {
+            tracing::error!("resolve item {} error: {}", name, err);
+            None
+        }
+    }
+}
+
+/// 套装的部件, 没有储存过(或不是套装)时为`None`.
+pub fn set_parts(set_url_name: &str) -> anyhow::Result<Option<Vec<SetPart>>> {
+    match ITEMS_DB
+        .open_tree(ITEM_SETS_TREE)?
+        .get(set_url_name.as_bytes())?
+    {
+        Some(parts) => Ok(Some(serde_json::from_slice(parts.as_ref())?)),
+        None => Ok(None),
+    }
+}
+
+/// 部件所属套装的`url_name`
+pub fn part_set(url_name: &str) -> sled::Result<Option<String>> {
+    Ok(ITEMS_DB
+        .open_tree(PART_SETS_TREE)?
+        .get(url_name.as_bytes())?
+        .map(|set| String::from_utf8_lossy(set.as_ref()).into_owned()))
+}
+
+/// 物品的详细信息, 没有储存过时为`None`.
+pub fn item_meta(url_name: &str) -> anyhow::Result<Option<ItemMeta>> {
+    match ITEMS_DB
+        .open_tree(ITEM_META_TREE)?
+        .get(url_name.as_bytes())?
+    {
+        Some(meta) => Ok(Some(serde_json::from_slice(meta.as_ref())?)),
+        None => Ok(None),
+    }
+}
+
+/// `timing::ducats`缓存的杜卡特效率排行, 还没有计算过时为`None`.
+pub fn ducat_ranking(platform: Platform) -> anyhow::Result<Option<DucatRanking>> {
+    match ITEMS_DB
+        .open_tree(DUCAT_RANKINGS_TREE)?
+        .get(platform.market())?
+    {
+        Some(ranking) => Ok(Some(serde_json::from_slice(ranking.as_ref())?)),
+        None => Ok(None),
+    }
+}
+
+pub fn riven_weapon(weapon_url_name: &str) -> anyhow::Result<Option<RivenWeapon>> {
+    match RIVENS_DB
+        .open_tree(RIVEN_WEAPONS_TREE)?
+        .get(weapon_url_name.as_bytes())?
+    {
+        Some(weapon) => Ok(Some(serde_json::from_slice(weapon.as_ref())?)),
+        None => Ok(None),
+    }
+}
+
+/// 武器的紫卡类型, 如`rifle`, `melee`.
+pub fn riven_type(weapon_url_name: &str) -> anyhow::Result<Option<String>> {
+    Ok(riven_weapon(weapon_url_name)?.and_then(|weapon| weapon.riven_type.map(|t| t.to_string())))
+}
+
+pub fn riven_attr_by_url_name(url_name: &str) -> anyhow::Result<Option<RivenAttribute>> {
+    match RIVENS_DB
+        .open_tree(RIVEN_ATTRS_TREE)?
+        .get(url_name.as_bytes())?
+    {
+        Some(attr) => Ok(Some(serde_json::from_slice(attr.as_ref())?)),
+        None => Ok(None),
+    }
+}
+
+/// 紫卡词条是否已经储存, 没有储存时不能查找词条, 也不知道词条限定的紫卡类型.
+pub fn riven_attrs_stored() -> sled::Result<bool> {
+    Ok(!RIVENS_DB.open_tree(RIVEN_ATTRS_TREE)?.is_empty())
+}
+
+/// 用词条名, 简称或别名查找词条, 词条还没有储存时为`None`.
+pub fn riven_attr(name: &str) -> anyhow::Result<Option<RivenAttribute>> {
+    match RIVENS_DB
+        .open_tree(RIVEN_ATTR_NAMES_TREE)?
+        .get(riven_attr_key(name))?
+    {
+        Some(url_name) => riven_attr_by_url_name(&String::from_utf8_lossy(url_name.as_ref())),
+        None => Ok(None),
+    }
+}
+
+/// 词条的所有名字(中文名, 简称和别名)
+pub fn riven_attr_names(url_name: &str) -> sled::Result<Vec<String>> {
+    RIVENS_DB
+        .open_tree(RIVEN_ATTR_NAMES_TREE)?
+        .iter()
+        .filter_map(|entry| match entry {
+            Ok((name, value)) if value.as_ref() == url_name.as_bytes() => {
+                Some(Ok(String::from_utf8_lossy(name.as_ref()).into_owned()))
+            }
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect()
+}
+
+/// 添加词条别名, 别名已经是某个词条的名字时返回`false`.
+pub fn add_riven_attr_alias(alias: &str, url_name: &str) -> sled::Result<bool> {
+    let names = RIVENS_DB.open_tree(RIVEN_ATTR_NAMES_TREE)?;
+    let key = riven_attr_key(alias);
+    if names.contains_key(&key)? {
+        return Ok(false);
+    }
+    RIVENS_DB
+        .open_tree(RIVEN_ATTR_ALIASES_TREE)?
+        .insert(&key, url_name.as_bytes())?;
+    names.insert(&key, url_name.as_bytes())?;
+    Ok(true)
+}
+
+/// 删除用户添加的词条别名, 别名不存在时返回`false`.
+pub fn remove_riven_attr_alias(alias: &str) -> sled::Result<bool> {
+    let key = riven_attr_key(alias);
+    if RIVENS_DB
+        .open_tree(RIVEN_ATTR_ALIASES_TREE)?
+        .remove(&key)?
+        .is_none()
+    {
+        return Ok(false);
+    }
+    RIVENS_DB.open_tree(RIVEN_ATTR_NAMES_TREE)?.remove(&key)?;
+    Ok(true)
+}
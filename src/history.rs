@@ -0,0 +1,55 @@
+//! 定时任务看到的仲裁记录, 用来核对轮换表. 储存在sled中, 每个平台一个tree, key为开始时间戳.
+
+use compact_str::CompactStr;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::db::HISTORY_DB;
+use crate::wf_api::{Arbitration, ArbitrationLevel, Enemy, Platform};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArbitrationRecord {
+    #[serde(with = "time::serde::timestamp")]
+    pub activation: OffsetDateTime,
+    pub node: CompactStr,
+    pub node_key: Option<CompactStr>,
+    pub r#type: CompactStr,
+    pub type_key: CompactStr,
+    pub enemy: Enemy,
+    /// 记录时的评级
+    pub tier: ArbitrationLevel,
+}
+
+fn tree(platform: Platform) -> sled::Result<sled::Tree> {
+    HISTORY_DB.open_tree(format!("arbitration_{}", platform.worldstate()))
+}
+
+pub fn record_arbitration(platform: Platform, data: &Arbitration) -> anyhow::Result<()> {
+    let record = ArbitrationRecord {
+        activation: data.activation,
+        node: data.node.clone(),
+        node_key: data.node_key.clone(),
+        r#type: data.r#type.clone(),
+        type_key: data.type_key.clone(),
+        enemy: data.enemy.clone(),
+        tier: ArbitrationLevel::from_data(data),
+    };
+    tree(platform)?.insert(
+        data.activation.unix_timestamp().to_be_bytes(),
+        serde_json::to_vec(&record)?,
+    )?;
+    Ok(())
+}
+
+/// 最近的`limit`条记录, 新的在前.
+pub fn recent_arbitrations(
+    platform: Platform,
+    limit: usize,
+) -> anyhow::Result<Vec<ArbitrationRecord>> {
+    tree(platform)?
+        .iter()
+        .rev()
+        .take(limit)
+        .map(|entry| Ok(serde_json::from_slice(&entry?.1)?))
+        .collect()
+}
@@ -1,5 +1,6 @@
 use std::str::FromStr;
 
+use once_cell::sync::Lazy;
 use proc_qq::Authentication;
 use proc_qq::ClientBuilder;
 use proc_qq::DeviceSource::JsonFile;
@@ -7,10 +8,20 @@ use tracing::Level;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
-use crate::mods::{active_arb, eidolon, invite, wm};
+use crate::mods::{
+    active_arb, alias, arbitration_tier, bounty, cycle, ducats, eidolon, fissure, invasion, invite,
+    nightwave, platform, riven_attr, sortie, subscribe, void_trader, watch, wm,
+};
 
+mod aliases;
+mod db;
+mod history;
 mod mods;
+mod resolve;
+mod settings;
+mod subscription;
 mod timing;
+mod watches;
 pub mod wf_api;
 
 #[tokio::main]
@@ -18,6 +29,8 @@ async fn main() -> anyhow::Result<()> {
     dotenv::dotenv()?;
 
     init_tracing_subscriber()?;
+    Lazy::force(&wf_api::WF_API);
+
     let client = ClientBuilder::new()
         .version(proc_qq::re_exports::ricq::version::IPAD)
         .device(JsonFile("device.json".to_owned()))
@@ -27,16 +40,39 @@ async fn main() -> anyhow::Result<()> {
         ))
         .modules(vec![
             active_arb::module(),
+            arbitration_tier::module(),
             invite::module(),
             eidolon::module(),
+            cycle::module(),
+            bounty::module(),
             wm::module(),
+            riven_attr::module(),
+            alias::module(),
+            fissure::module(),
+            invasion::module(),
+            nightwave::module(),
+            sortie::module(),
+            void_trader::module(),
+            ducats::module(),
+            watch::module(),
+            subscribe::module(),
+            platform::module(),
         ])
         .build()
         .await?;
 
     let rq_client = &client.rq_client;
     timing::arbitration(rq_client.clone());
-    timing::eidolon(rq_client.clone());
+    timing::cycle(rq_client.clone());
+    timing::bounty(rq_client.clone());
+    timing::invasion(rq_client.clone());
+    timing::weekly_reset(rq_client.clone());
+    timing::fissure(rq_client.clone());
+    timing::sortie(rq_client.clone());
+    timing::void_trader(rq_client.clone());
+    timing::catalog(rq_client.clone());
+    timing::ducats();
+    timing::price_watch(rq_client.clone());
 
     client.start().await??;
     Ok(())
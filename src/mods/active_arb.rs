@@ -2,13 +2,100 @@ use proc_qq::{
     event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
     MessageSendToSourceTrait, Module,
 };
+use time::OffsetDateTime;
 
-use crate::wf_api::{arbitration, gen_arbitration_info};
+use crate::history;
+use crate::mods::{query_platform, reply_api_error};
+use crate::wf_api::{
+    arbitration_forecast, format_time, gen_arbitration_info, gen_forecast_info, gen_scheduled_line,
+    next_good_arbitration, scheduled_arbitration, WF_API,
+};
+
+/// 默认预报的小时数
+const FORECAST_HOURS: i64 = 6;
+const MAX_FORECAST_HOURS: i64 = 48;
+/// `仲裁 历史`列出的条数
+const HISTORY_LIMIT: usize = 12;
+
+/// 最近的仲裁记录, 并和轮换表核对.
+fn history_info(records: &[history::ArbitrationRecord]) -> String {
+    if records.is_empty() {
+        return "还没有仲裁记录".to_owned();
+    }
+
+    records
+        .iter()
+        .map(|record| {
+            let node_key = record.node_key.as_deref().unwrap_or(&record.node);
+            let check = match scheduled_arbitration(record.activation) {
+                Some(scheduled) if scheduled.node.eq_ignore_ascii_case(node_key) => {
+                    "预报准确".to_owned()
+                }
+                Some(scheduled) => format!("预报为 {}", scheduled.node),
+                None => "无预报".to_owned(),
+            };
+            format!(
+                "{} {} {} [{:?}] {}",
+                format_time(record.activation),
+                record.node,
+                record.r#type,
+                record.tier,
+                check
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
 #[event]
 async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
     let content = event.message_content();
-    if content == "仲裁" {
+    if let Some(param) = content.strip_prefix("仲裁") {
+        let (platform, param) = query_platform(event, param)?;
+        let (sub, arg) = param.split_once(' ').unwrap_or((param, ""));
+        match sub {
+            "" => {}
+            "预报" => {
+                let hours = match arg.trim() {
+                    "" => FORECAST_HOURS,
+                    hours => match hours.trim_end_matches("小时").parse::<i64>() {
+                        Ok(hours) => hours.clamp(1, MAX_FORECAST_HOURS),
+                        Err(_) => {
+                            event
+                                .send_message_to_source(
+                                    "格式: 仲裁 预报 [小时数]".parse_message_chain(),
+                                )
+                                .await?;
+                            return Ok(true);
+                        }
+                    },
+                };
+                let forecast = arbitration_forecast(OffsetDateTime::now_utc(), hours);
+                event
+                    .send_message_to_source(gen_forecast_info(&forecast))
+                    .await?;
+                return Ok(true);
+            }
+            "下次好图" => {
+                let reply = match next_good_arbitration(OffsetDateTime::now_utc()) {
+                    Some(next) => format!("下次好图: {}", gen_scheduled_line(&next)),
+                    None => "轮换表里没有接下来的好图".to_owned(),
+                };
+                event
+                    .send_message_to_source(reply.parse_message_chain())
+                    .await?;
+                return Ok(true);
+            }
+            "历史" => {
+                let records = history::recent_arbitrations(platform, HISTORY_LIMIT)?;
+                event
+                    .send_message_to_source(history_info(&records).parse_message_chain())
+                    .await?;
+                return Ok(true);
+            }
+            _ => return Ok(false),
+        }
+
         if rand::random::<u8>() % 64 == 0 {
             event
                 .send_message_to_source(format!(
@@ -20,23 +107,14 @@ async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
                     level = "好图",
                 ).parse_message_chain()).await?;
         } else {
-            match arbitration().await {
+            match WF_API.arbitration(platform).await {
                 Ok(data) => {
                     event
                         .send_message_to_source(gen_arbitration_info(&data))
                         .await?;
                 }
                 Err(err) => {
-                    tracing::warn!("arbitration error: {}", err);
-                    event
-                        .send_message_to_source(format!(
-                            "节点: {node} \n剩余时间(约): {time} 分钟 \n类型: {ty} \n敌人: {enemy} \n个人评价: {level}",
-                            node = "SaveMother (地府)",
-                            time = -1,
-                            ty = "救援",
-                            enemy = "de的妈",
-                            level = "好图, 但你不能救一个不存在的生物",
-                        ).parse_message_chain()).await?;
+                    reply_api_error(event, "仲裁", &err).await?;
                 }
             };
         }
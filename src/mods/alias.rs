@@ -0,0 +1,117 @@
+use itertools::Itertools;
+use proc_qq::{
+    event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
+    MessageSendToSourceTrait, Module,
+};
+
+use crate::aliases::{add_alias, aliases, remove_alias, AliasScope};
+use crate::db::{ITEMS_INDEX, RIVENS_INDEX};
+use crate::mods::{is_group_admin, is_owner, source_group};
+use crate::resolve::resolve_name;
+
+const USAGE: &str = "别名 添加 [全局] <别名> <物品名>: 添加别名, 如: 别名 添加 电男套 voltp套\n\
+    别名 删除 [全局] <别名>: 删除别名\n\
+    别名 列表: 查看本群和全局的别名\n\
+    群别名由群管理修改, 全局别名由主人修改";
+
+/// 别名指向的物品或紫卡武器名, 必须唯一匹配, 只是名字的开头(有多个匹配)时为`None`.
+fn canonical_target(target: &str) -> sled::Result<Option<String>> {
+    for names in [&*ITEMS_INDEX, &*RIVENS_INDEX] {
+        if let Some(name) = resolve_name(names, target)? {
+            return Ok(Some(name));
+        }
+    }
+    Ok(None)
+}
+
+fn alias_list(scope: AliasScope) -> sled::Result<String> {
+    let aliases = aliases(scope)?;
+    Ok(if aliases.is_empty() {
+        format!("{}没有别名", scope.name())
+    } else {
+        format!(
+            "{}别名:\n{}",
+            scope.name(),
+            aliases
+                .iter()
+                .map(|(alias, target)| format!("{} => {}", alias, target))
+                .join("\n")
+        )
+    })
+}
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    let param = match content.strip_prefix("别名") {
+        Some(param) => param.trim(),
+        None => return Ok(false),
+    };
+    let group = source_group(event);
+
+    let (sub, arg) = param.split_once(' ').unwrap_or((param, ""));
+    let (global, arg) = match arg.trim().strip_prefix("全局") {
+        Some(arg) => (true, arg.trim()),
+        None => (false, arg.trim()),
+    };
+    let scope = match group {
+        Some(group) if !global => AliasScope::Group(group),
+        _ => AliasScope::Global,
+    };
+
+    let reply = match sub {
+        "列表" => match group {
+            Some(group) => format!(
+                "{}\n{}",
+                alias_list(AliasScope::Group(group))?,
+                alias_list(AliasScope::Global)?
+            ),
+            None => alias_list(AliasScope::Global)?,
+        },
+        "添加" | "删除" => {
+            let allowed = match scope {
+                AliasScope::Global => is_owner(event)?,
+                AliasScope::Group(_) => is_owner(event)? || is_group_admin(event).await?,
+            };
+            if !allowed {
+                format!(
+                    "只有{}可以修改{}别名",
+                    match scope {
+                        AliasScope::Global => "主人",
+                        AliasScope::Group(_) => "群管理",
+                    },
+                    scope.name()
+                )
+            } else if sub == "删除" {
+                if arg.is_empty() {
+                    USAGE.to_owned()
+                } else if remove_alias(scope, arg)? {
+                    format!("已删除{}别名 {}", scope.name(), arg)
+                } else {
+                    format!("{}没有别名 {}", scope.name(), arg)
+                }
+            } else {
+                match arg.split_once(' ') {
+                    None => USAGE.to_owned(),
+                    Some((alias, target)) => match canonical_target(target)? {
+                        None => format!("找不到唯一的物品或紫卡武器 {}", target.trim()),
+                        Some(target) => {
+                            add_alias(scope, alias, &target)?;
+                            format!("已添加{}别名: {} => {}", scope.name(), alias, target)
+                        }
+                    },
+                }
+            }
+        }
+        _ => USAGE.to_owned(),
+    };
+
+    event
+        .send_message_to_source(reply.parse_message_chain())
+        .await?;
+    Ok(true)
+}
+
+pub fn module() -> Module {
+    module!("alias", "别名", cmd)
+}
@@ -0,0 +1,99 @@
+use compact_str::CompactStr;
+use itertools::Itertools;
+use proc_qq::{
+    event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
+    MessageSendToSourceTrait, Module,
+};
+
+use crate::mods::is_owner;
+use crate::wf_api::{
+    remove_tier_rule, set_tier_rule, tier_rules, ArbitrationLevel, Enemy, TierRule,
+};
+
+const USAGE: &str = "仲裁评级 <节点关键词>: 查看评级\n\
+    仲裁评级 <节点>, <任务类型>, [敌人,] <T0/T1/Bad/删除>: 修改评级\n\
+    节点和任务类型用英文, 只写星球名时匹配整个星球, 如: 仲裁评级 Hydron (Sedna), Defense, T0";
+
+fn rule_line(rule: &TierRule) -> String {
+    let mut line = format!("{} {}", rule.node, rule.r#type);
+    if let Some(enemy) = &rule.enemy {
+        line.push_str(&format!(" [{}]", enemy.key()));
+    }
+    format!("{} => {:?} ({})", line, rule.tier, rule.tier.nickname())
+}
+
+/// 解析`<节点>, <任务类型>, [敌人,] <评级>`并修改评级表, 返回回复.
+fn change_tier(param: &str) -> anyhow::Result<String> {
+    let parts = param.split([',', '，']).map(str::trim).collect::<Vec<_>>();
+    let (node, ty, enemy, tier) = match parts.as_slice() {
+        [node, ty, tier] => (*node, *ty, None, *tier),
+        [node, ty, enemy, tier] => match Enemy::from_name(enemy) {
+            Some(enemy) => (*node, *ty, Some(enemy), *tier),
+            None => {
+                return Ok(format!(
+                    "不认识的敌人: {}, 可选: {}",
+                    enemy,
+                    Enemy::ALL.iter().map(|e| e.key()).join(", ")
+                ))
+            }
+        },
+        _ => return Ok(USAGE.to_owned()),
+    };
+    if node.is_empty() || ty.is_empty() {
+        return Ok(USAGE.to_owned());
+    }
+
+    if tier == "删除" {
+        return Ok(if remove_tier_rule(node, ty, enemy)? {
+            "已删除".to_owned()
+        } else {
+            "评级表中没有这条规则".to_owned()
+        });
+    }
+
+    let rule = match ArbitrationLevel::from_name(tier) {
+        Some(tier) => TierRule {
+            node: CompactStr::new(node),
+            r#type: CompactStr::new(ty),
+            enemy,
+            tier,
+        },
+        None => return Ok("评级只能是T0, T1, Bad或删除".to_owned()),
+    };
+    let reply = format!("已设置: {}", rule_line(&rule));
+    set_tier_rule(rule)?;
+    Ok(reply)
+}
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    let param = match content.strip_prefix("仲裁评级") {
+        Some(param) => param.trim(),
+        None => return Ok(false),
+    };
+
+    let reply = if param.is_empty() {
+        USAGE.to_owned()
+    } else if !param.contains([',', '，']) {
+        let rules = tier_rules(param);
+        if rules.is_empty() {
+            format!("评级表中没有节点包含 {} 的规则", param)
+        } else {
+            rules.iter().map(rule_line).join("\n")
+        }
+    } else if !is_owner(event)? {
+        "只有主人可以修改仲裁评级".to_owned()
+    } else {
+        change_tier(param)?
+    };
+
+    event
+        .send_message_to_source(reply.parse_message_chain())
+        .await?;
+    Ok(true)
+}
+
+pub fn module() -> Module {
+    module!("arbitration_tier", "仲裁评级", cmd)
+}
@@ -0,0 +1,56 @@
+use itertools::Itertools;
+use proc_qq::{
+    event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
+    MessageSendToSourceTrait, Module,
+};
+
+use crate::mods::{query_platform, reply_api_error};
+use crate::wf_api::{gen_bounties_info, BountySyndicate, WF_API};
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    if let Some(name) = content.strip_prefix("赏金") {
+        let (platform, name) = query_platform(event, name)?;
+        let syndicate = match BountySyndicate::from_alias(name) {
+            Some(syndicate) => syndicate,
+            None => {
+                event
+                    .send_message_to_source(
+                        format!(
+                            "格式: 赏金 [平台] <集团>, 可选: {}",
+                            BountySyndicate::ALL.iter().map(|s| s.chinese()).join(", ")
+                        )
+                        .parse_message_chain(),
+                    )
+                    .await?;
+                return Ok(true);
+            }
+        };
+
+        match WF_API.bounties(platform, syndicate).await {
+            Ok(Some(data)) => {
+                event
+                    .send_message_to_source(gen_bounties_info(syndicate, &data))
+                    .await?;
+            }
+            Ok(None) => {
+                event
+                    .send_message_to_source(
+                        format!("现在没有{}的赏金", syndicate.chinese()).parse_message_chain(),
+                    )
+                    .await?;
+            }
+            Err(err) => {
+                reply_api_error(event, "赏金", &err).await?;
+            }
+        };
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+pub fn module() -> Module {
+    module!("bounty", "赏金", cmd)
+}
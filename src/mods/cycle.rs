@@ -0,0 +1,40 @@
+use proc_qq::{
+    event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
+    MessageSendToSourceTrait, Module,
+};
+
+use crate::mods::query_platform;
+use crate::wf_api::{gen_cycle_line, World, WF_API};
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    let (command, param) = content.split_once(' ').unwrap_or((content.as_str(), ""));
+    if command == "周期" {
+        let (platform, param) = query_platform(event, param)?;
+        if !param.is_empty() {
+            return Ok(false);
+        }
+        let mut info = String::with_capacity(256);
+        for world in World::ALL {
+            match WF_API.world_cycle(platform, *world).await {
+                Ok(data) => info.push_str(&gen_cycle_line(*world, &data)),
+                Err(err) => {
+                    tracing::warn!("{:?} cycle error: {}", world, err);
+                    info.push_str(&format!("{}: 获取失败", world.chinese()));
+                }
+            }
+            info.push('\n');
+        }
+        event
+            .send_message_to_source(info.trim_end().parse_message_chain())
+            .await?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+pub fn module() -> Module {
+    module!("cycle", "周期", cmd)
+}
@@ -0,0 +1,63 @@
+use proc_qq::{
+    event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
+    MessageSendToSourceTrait, Module,
+};
+
+use crate::db::ducat_ranking;
+use crate::mods::query_platform;
+use crate::wf_api::gen_ducat_ranking;
+
+/// 卖家太少的部件价格不可靠, 默认不列出.
+const DEFAULT_MIN_SELLERS: usize = 3;
+const DEFAULT_COUNT: usize = 10;
+const MAX_COUNT: usize = 30;
+
+const USAGE: &str = "杜卡特 [平台] [卖家>=N] [条数:N]: 每白金能换到最多杜卡特的prime部件, 如: 杜卡特 卖家>=5 条数:15";
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    let param = match content.strip_prefix("杜卡特") {
+        Some(param) => param.trim(),
+        None => return Ok(false),
+    };
+    let (platform, param) = query_platform(event, param)?;
+
+    let mut min_sellers = DEFAULT_MIN_SELLERS;
+    let mut count = DEFAULT_COUNT;
+    for option in param.split_whitespace() {
+        let parsed = if let Some(n) = option.strip_prefix("卖家>=") {
+            n.parse().map(|n| min_sellers = n).is_ok()
+        } else if let Some(n) = option.strip_prefix("条数:") {
+            n.parse::<usize>()
+                .map(|n| count = n.clamp(1, MAX_COUNT))
+                .is_ok()
+        } else {
+            false
+        };
+        if !parsed {
+            event
+                .send_message_to_source(
+                    format!("不认识的参数: {}\n{}", option, USAGE).parse_message_chain(),
+                )
+                .await?;
+            return Ok(true);
+        }
+    }
+
+    let reply = match ducat_ranking(platform)? {
+        Some(ranking) => gen_ducat_ranking(platform, &ranking, min_sellers, count),
+        None => format!(
+            "{} 的杜卡特效率还没有统计, 只会统计各群设置的平台, 刚启动时要等物品信息更新完",
+            platform.name()
+        ),
+    };
+    event
+        .send_message_to_source(reply.parse_message_chain())
+        .await?;
+    Ok(true)
+}
+
+pub fn module() -> Module {
+    module!("ducats", "杜卡特", cmd)
+}
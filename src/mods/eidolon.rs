@@ -1,25 +1,23 @@
-use proc_qq::{
-    event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
-    MessageSendToSourceTrait, Module,
-};
+use proc_qq::{event, module, MessageContentTrait, MessageEvent, MessageSendToSourceTrait, Module};
 
-use crate::wf_api::{cetus_cycle, gen_cetus_info};
+use crate::mods::{query_platform, reply_api_error};
+use crate::wf_api::{gen_cycle_info, World, WF_API};
 
 #[event]
 async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
     let content = event.message_content();
-    if content == "三傻" || content =="3傻" || content == "夜灵" {
-        match cetus_cycle().await {
+    let (command, param) = content.split_once(' ').unwrap_or((content.as_str(), ""));
+    if matches!(command, "三傻" | "3傻" | "夜灵") {
+        let (platform, param) = query_platform(event, param)?;
+        if !param.is_empty() {
+            return Ok(false);
+        }
+        match WF_API.world_cycle(platform, World::Cetus).await {
             Ok(data) => {
-                event.send_message_to_source(gen_cetus_info(&data)).await?;
+                event.send_message_to_source(gen_cycle_info(&data)).await?;
             }
             Err(err) => {
-                tracing::warn!("eidolon error: {}", err);
-                event
-                    .send_message_to_source(
-                        "希图斯状态接口出现了错误, 等等再试吧".parse_message_chain(),
-                    )
-                    .await?;
+                reply_api_error(event, "希图斯状态", &err).await?;
             }
         };
         Ok(true)
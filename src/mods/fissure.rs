@@ -0,0 +1,31 @@
+use proc_qq::{event, module, MessageContentTrait, MessageEvent, MessageSendToSourceTrait, Module};
+
+use crate::mods::{query_platform, reply_api_error};
+use crate::wf_api::{gen_fissures_info, WF_API};
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    if let Some(filter) = content.strip_prefix("裂缝") {
+        let (platform, filter) = query_platform(event, filter)?;
+        match WF_API.fissures(platform).await {
+            Ok(data) => {
+                event
+                    .send_message_to_source(gen_fissures_info(
+                        data.iter().filter(|f| f.matches(filter)),
+                    ))
+                    .await?;
+            }
+            Err(err) => {
+                reply_api_error(event, "裂缝", &err).await?;
+            }
+        };
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+pub fn module() -> Module {
+    module!("fissure", "裂缝", cmd)
+}
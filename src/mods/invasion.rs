@@ -0,0 +1,33 @@
+use proc_qq::{event, module, MessageContentTrait, MessageEvent, MessageSendToSourceTrait, Module};
+
+use crate::mods::{query_platform, reply_api_error};
+use crate::wf_api::{gen_invasions_info, WF_API};
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    let (command, param) = content.split_once(' ').unwrap_or((content.as_str(), ""));
+    if command == "入侵" {
+        let (platform, param) = query_platform(event, param)?;
+        if !param.is_empty() {
+            return Ok(false);
+        }
+        match WF_API.invasions(platform).await {
+            Ok(data) => {
+                event
+                    .send_message_to_source(gen_invasions_info(&data))
+                    .await?;
+            }
+            Err(err) => {
+                reply_api_error(event, "入侵", &err).await?;
+            }
+        };
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+pub fn module() -> Module {
+    module!("invasion", "入侵", cmd)
+}
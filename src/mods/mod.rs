@@ -1,4 +1,156 @@
+use proc_qq::re_exports::ricq::structs::GroupMemberPermission;
+use proc_qq::{MessageChainParseTrait, MessageEvent, MessageSendToSourceTrait};
+
+use crate::aliases::apply_aliases;
+use crate::resolve::{resolve, NameIndex, Resolved};
+use crate::settings;
+use crate::wf_api::{OrderFilter, Platform, WfApiError};
+
 pub mod active_arb;
+pub mod alias;
+pub mod arbitration_tier;
+pub mod bounty;
+pub mod cycle;
+pub mod ducats;
 pub mod eidolon;
+pub mod fissure;
+pub mod invasion;
 pub mod invite;
+pub mod nightwave;
+pub mod platform;
+pub mod riven_attr;
+pub mod sortie;
+pub mod subscribe;
+pub mod void_trader;
+pub mod watch;
 pub mod wm;
+
+/// 发送者是否是`.env`中的`owner`.
+pub fn is_owner(event: &MessageEvent) -> anyhow::Result<bool> {
+    Ok(event.from_uin() == dotenv::var("owner")?.parse::<i64>()?)
+}
+
+/// 发送者是否是群主或管理员, 私聊为`false`.
+pub async fn is_group_admin(event: &MessageEvent) -> anyhow::Result<bool> {
+    match event.as_group_message() {
+        Ok(group) => {
+            let member = group
+                .client
+                .get_group_member_info(group.inner.group_code, group.inner.from_uin)
+                .await?;
+            Ok(!matches!(member.permission, GroupMemberPermission::Member))
+        }
+        Err(_) => Ok(false),
+    }
+}
+
+/// 消息来源的群号, 私聊为`None`.
+pub fn source_group(event: &MessageEvent) -> Option<i64> {
+    event
+        .as_group_message()
+        .ok()
+        .map(|group| group.inner.group_code)
+}
+
+/// 消息来源的默认平台, 群里按群设置, 私聊为PC.
+pub fn source_platform(event: &MessageEvent) -> anyhow::Result<Platform> {
+    match event.as_group_message() {
+        Ok(group) => Ok(settings::platform(group.inner.group_code)?),
+        Err(_) => Ok(Platform::Pc),
+    }
+}
+
+/// 消息来源的默认wm订单过滤条件, 群里按群设置, 私聊为默认值.
+pub fn source_order_filter(event: &MessageEvent) -> anyhow::Result<OrderFilter> {
+    match event.as_group_message() {
+        Ok(group) => settings::order_filter(group.inner.group_code),
+        Err(_) => Ok(OrderFilter::default()),
+    }
+}
+
+/// 参数开头指定了平台(如`ps4 赤毒`)时用指定的, 否则用`source_platform`. 返回平台和剩下的参数.
+pub fn query_platform<'a>(
+    event: &MessageEvent,
+    param: &'a str,
+) -> anyhow::Result<(Platform, &'a str)> {
+    match Platform::split_prefix(param) {
+        (Some(platform), rest) => Ok((platform, rest)),
+        (None, rest) => Ok((source_platform(event)?, rest)),
+    }
+}
+
+/// 替换别名后用`resolve`查找物品的`url_name`, 找不到时回复`not_found`和最接近的名字.
+pub async fn resolve_item(
+    event: &MessageEvent,
+    names: &NameIndex,
+    name: &str,
+    not_found: &str,
+) -> anyhow::Result<Option<String>> {
+    match resolve(names, &apply_aliases(source_group(event), name)?)? {
+        Resolved::Found(url_name) => Ok(Some(url_name)),
+        Resolved::NotFound(suggestions) => {
+            let mut reply = format!("{} {}", not_found, name);
+            if !suggestions.is_empty() {
+                reply.push_str(&format!("\n你是不是要找: {}", suggestions.join(", ")));
+            }
+            event
+                .send_message_to_source(reply.parse_message_chain())
+                .await?;
+            Ok(None)
+        }
+    }
+}
+
+/// 按错误类型记录日志, 并回复对应的提示. `source`为出错的接口名, 如"仲裁".
+pub async fn reply_api_error(
+    event: &MessageEvent,
+    source: &str,
+    err: &WfApiError,
+) -> anyhow::Result<()> {
+    let reply = match err {
+        WfApiError::Timeout => {
+            tracing::warn!("{} api timeout", source);
+            format!("{}接口超时了, 等等再试吧", source)
+        }
+        WfApiError::Network(err) => {
+            tracing::warn!("{} api network error: {}", source, err);
+            format!("连不上{}接口, 可能是网络问题, 等等再试吧", source)
+        }
+        WfApiError::RateLimited { retry_after } => {
+            tracing::warn!("{} api rate limited", source);
+            match retry_after {
+                Some(after) => format!(
+                    "查得太频繁被{}接口限流了, {} 秒后再试吧",
+                    source,
+                    after.as_secs()
+                ),
+                None => format!("查得太频繁被{}接口限流了, 过一会再试吧", source),
+            }
+        }
+        WfApiError::NotFound => {
+            tracing::debug!("{} api not found", source);
+            format!("{}接口说找不到相关数据", source)
+        }
+        WfApiError::Status(status) if status.is_server_error() => {
+            tracing::warn!("{} api server error: {}", source, status);
+            format!(
+                "{}接口的服务器炸了 ({}), 等等再试吧",
+                source,
+                status.as_u16()
+            )
+        }
+        WfApiError::Status(status) => {
+            tracing::error!("{} api unexpected status: {}", source, status);
+            format!("{}接口返回了意外的状态 ({})", source, status.as_u16())
+        }
+        WfApiError::Decode { error, body } => {
+            tracing::error!("{} api decode error: {}, body: {}", source, error, body);
+            format!("{}接口的数据格式变了, 需要更新机器人", source)
+        }
+    };
+
+    event
+        .send_message_to_source(reply.parse_message_chain())
+        .await?;
+    Ok(())
+}
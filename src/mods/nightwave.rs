@@ -0,0 +1,33 @@
+use proc_qq::{event, module, MessageContentTrait, MessageEvent, MessageSendToSourceTrait, Module};
+
+use crate::mods::{query_platform, reply_api_error};
+use crate::wf_api::{gen_nightwave_info, WF_API};
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    let (command, param) = content.split_once(' ').unwrap_or((content.as_str(), ""));
+    if command == "午夜电波" || command == "电波" {
+        let (platform, param) = query_platform(event, param)?;
+        if !param.is_empty() {
+            return Ok(false);
+        }
+        match WF_API.nightwave(platform).await {
+            Ok(data) => {
+                event
+                    .send_message_to_source(gen_nightwave_info(&data))
+                    .await?;
+            }
+            Err(err) => {
+                reply_api_error(event, "午夜电波", &err).await?;
+            }
+        };
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+pub fn module() -> Module {
+    module!("nightwave", "午夜电波", cmd)
+}
@@ -0,0 +1,53 @@
+use itertools::Itertools;
+use proc_qq::{
+    event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
+    MessageSendToSourceTrait, Module,
+};
+
+use crate::mods::{is_group_admin, is_owner, source_platform};
+use crate::settings;
+use crate::wf_api::Platform;
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    let param = match content.strip_prefix("平台") {
+        Some(param) => param.trim(),
+        None => return Ok(false),
+    };
+
+    let reply = if param.is_empty() {
+        format!(
+            "当前平台: {}\n可选: {}",
+            source_platform(event)?.name(),
+            Platform::ALL.iter().map(|p| p.worldstate()).join(", ")
+        )
+    } else {
+        match (event.as_group_message(), Platform::from_alias(param)) {
+            (Err(_), _) => {
+                "只能设置群的默认平台, 私聊查询时可以在命令后加平台, 如: 仲裁 ps4".to_owned()
+            }
+            (Ok(_), None) => format!(
+                "不认识的平台: {}, 可选: {}",
+                param,
+                Platform::ALL.iter().map(|p| p.worldstate()).join(", ")
+            ),
+            (Ok(_), Some(_)) if !(is_owner(event)? || is_group_admin(event).await?) => {
+                "只有群管理可以修改本群的默认平台".to_owned()
+            }
+            (Ok(group), Some(platform)) => {
+                settings::set_platform(group.inner.group_code, platform)?;
+                format!("本群的默认平台已设置为{}", platform.name())
+            }
+        }
+    };
+
+    event
+        .send_message_to_source(reply.parse_message_chain())
+        .await?;
+    Ok(true)
+}
+
+pub fn module() -> Module {
+    module!("platform", "平台", cmd)
+}
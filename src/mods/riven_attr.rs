@@ -0,0 +1,82 @@
+use itertools::Itertools;
+use proc_qq::{
+    event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
+    MessageSendToSourceTrait, Module,
+};
+
+use crate::db::{add_riven_attr_alias, remove_riven_attr_alias, riven_attr, riven_attr_names};
+use crate::mods::is_owner;
+use crate::wf_api::{riven_type_name, RivenAttribute};
+
+const USAGE: &str = "词条 <词条名>: 查看紫卡词条\n\
+    词条 别名 <别名> <词条名>: 添加别名\n\
+    词条 删除别名 <别名>: 删除别名";
+
+fn attr_info(attr: &RivenAttribute) -> anyhow::Result<String> {
+    let mut info = format!("{} ({})", attr.effect, attr.url_name);
+    if let Some(types) = &attr.exclusive_to {
+        info.push_str(&format!(
+            "\n仅限: {}",
+            types.iter().map(|t| riven_type_name(t)).join(", ")
+        ));
+    }
+    if attr.positive_only {
+        info.push_str("\n只能是正面词条");
+    }
+    if attr.negative_only {
+        info.push_str("\n只能是负面词条");
+    }
+    let names = riven_attr_names(&attr.url_name)?;
+    if !names.is_empty() {
+        info.push_str(&format!("\n名字: {}", names.join(", ")));
+    }
+    Ok(info)
+}
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    let param = match content.strip_prefix("词条") {
+        Some(param) => param.trim(),
+        None => return Ok(false),
+    };
+
+    let (sub, arg) = param.split_once(' ').unwrap_or((param, ""));
+    let reply = match sub {
+        "" => USAGE.to_owned(),
+        "别名" | "删除别名" if !is_owner(event)? => "只有主人可以修改词条别名".to_owned(),
+        "别名" => match arg.trim().split_once(' ') {
+            None => USAGE.to_owned(),
+            Some((alias, name)) => match riven_attr(name.trim())? {
+                None => format!("找不到词条: {}", name.trim()),
+                Some(attr) => {
+                    if add_riven_attr_alias(alias, &attr.url_name)? {
+                        format!("已添加别名: {} => {}", alias, attr.effect)
+                    } else {
+                        format!("{} 已经是词条名或别名了", alias)
+                    }
+                }
+            },
+        },
+        "删除别名" => {
+            if remove_riven_attr_alias(arg.trim())? {
+                "已删除".to_owned()
+            } else {
+                format!("没有别名 {}", arg.trim())
+            }
+        }
+        _ => match riven_attr(param)? {
+            Some(attr) => attr_info(&attr)?,
+            None => format!("找不到词条: {}, 可能需要先update_rivens_db", param),
+        },
+    };
+
+    event
+        .send_message_to_source(reply.parse_message_chain())
+        .await?;
+    Ok(true)
+}
+
+pub fn module() -> Module {
+    module!("riven_attr", "紫卡词条", cmd)
+}
@@ -0,0 +1,44 @@
+use proc_qq::{event, module, MessageContentTrait, MessageEvent, MessageSendToSourceTrait, Module};
+
+use crate::mods::{query_platform, reply_api_error};
+use crate::wf_api::{gen_archon_hunt_info, gen_sortie_info, WF_API};
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    let (command, param) = content.split_once(' ').unwrap_or((content.as_str(), ""));
+    if !matches!(command, "突击" | "执刑官" | "执行官") {
+        return Ok(false);
+    }
+    let (platform, param) = query_platform(event, param)?;
+    if !param.is_empty() {
+        return Ok(false);
+    }
+
+    if command == "突击" {
+        match WF_API.sortie(platform).await {
+            Ok(data) => {
+                event.send_message_to_source(gen_sortie_info(&data)).await?;
+            }
+            Err(err) => {
+                reply_api_error(event, "突击", &err).await?;
+            }
+        };
+    } else {
+        match WF_API.archon_hunt(platform).await {
+            Ok(data) => {
+                event
+                    .send_message_to_source(gen_archon_hunt_info(&data))
+                    .await?;
+            }
+            Err(err) => {
+                reply_api_error(event, "执刑官", &err).await?;
+            }
+        };
+    }
+    Ok(true)
+}
+
+pub fn module() -> Module {
+    module!("sortie", "突击", cmd)
+}
@@ -0,0 +1,111 @@
+use compact_str::CompactStr;
+use itertools::Itertools;
+use proc_qq::{
+    event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
+    MessageSendToSourceTrait, Module,
+};
+
+use crate::mods::{is_group_admin, is_owner};
+use crate::subscription::{self, Topic};
+
+const NOT_ADMIN: &str = "只有群管理可以修改本群的订阅";
+
+fn parse_topic(param: &str) -> Option<(Topic, Option<&str>)> {
+    let (name, filter) = match param.split_once(' ') {
+        Some((name, filter)) => (name, Some(filter.trim()).filter(|f| !f.is_empty())),
+        None => (param, None),
+    };
+    Topic::from_chinese(name).map(|topic| (topic, filter))
+}
+
+fn topics_hint() -> String {
+    format!(
+        "可订阅的提醒: {}",
+        Topic::ALL.iter().map(|t| t.chinese()).join(", ")
+    )
+}
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    let content = content.trim();
+    if !(content.starts_with("订阅") || content.starts_with("退订")) {
+        return Ok(false);
+    }
+
+    let group = match event.as_group_message() {
+        Ok(group) => group.inner.group_code,
+        Err(_) => {
+            event
+                .send_message_to_source("只能在群里订阅提醒".parse_message_chain())
+                .await?;
+            return Ok(true);
+        }
+    };
+
+    let reply = if content == "订阅列表" {
+        let mut list = String::new();
+        for topic in Topic::ALL {
+            if let Some(filters) = subscription::filters(*topic, group)? {
+                if filters.is_empty() {
+                    list.push_str(&format!("{}: 全部\n", topic.chinese()));
+                } else {
+                    list.push_str(&format!(
+                        "{}: {}\n",
+                        topic.chinese(),
+                        filters.iter().join(" | ")
+                    ));
+                }
+            }
+        }
+        if list.is_empty() {
+            format!("本群还没有订阅任何提醒\n{}", topics_hint())
+        } else {
+            list.trim_end().to_owned()
+        }
+    } else if let Some(param) = content.strip_prefix("订阅") {
+        match parse_topic(param.trim()) {
+            None => topics_hint(),
+            Some((topic, filter)) => match topic.normalize_filter(filter) {
+                Err(hint) => hint,
+                Ok(_) if !(is_owner(event)? || is_group_admin(event).await?) => {
+                    NOT_ADMIN.to_owned()
+                }
+                Ok(filter) => {
+                    subscription::subscribe(topic, group, filter.as_deref())?;
+                    match filter {
+                        Some(filter) => format!("已订阅{}提醒: {}", topic.chinese(), filter),
+                        None => format!("已订阅全部{}提醒", topic.chinese()),
+                    }
+                }
+            },
+        }
+    } else if let Some(param) = content.strip_prefix("退订") {
+        match parse_topic(param.trim()) {
+            None => topics_hint(),
+            Some(_) if !(is_owner(event)? || is_group_admin(event).await?) => NOT_ADMIN.to_owned(),
+            Some((topic, filter)) => {
+                let filter = match topic.normalize_filter(filter) {
+                    Ok(normalized) => normalized,
+                    Err(_) => filter.map(CompactStr::new),
+                };
+                if subscription::unsubscribe(topic, group, filter.as_deref())? {
+                    format!("已退订{}提醒", topic.chinese())
+                } else {
+                    format!("本群没有订阅这个{}提醒", topic.chinese())
+                }
+            }
+        }
+    } else {
+        return Ok(false);
+    };
+
+    event
+        .send_message_to_source(reply.parse_message_chain())
+        .await?;
+    Ok(true)
+}
+
+pub fn module() -> Module {
+    module!("subscribe", "订阅", cmd)
+}
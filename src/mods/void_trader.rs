@@ -0,0 +1,51 @@
+use proc_qq::{
+    event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
+    MessageSendToSourceTrait, Module,
+};
+
+use crate::db::exact_url_name;
+use crate::mods::{query_platform, reply_api_error};
+use crate::wf_api::{gen_void_trader_info, WF_API};
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    let (command, param) = content.split_once(' ').unwrap_or((content.as_str(), ""));
+    if command == "奸商" || command == "虚空商人" {
+        let (platform, param) = query_platform(event, param)?;
+        if !param.is_empty() {
+            return Ok(false);
+        }
+        let data = match WF_API.void_trader(platform).await {
+            Ok(data) => data,
+            Err(err) => {
+                reply_api_error(event, "虚空商人", &err).await?;
+                return Ok(true);
+            }
+        };
+
+        if !data.active {
+            event
+                .send_message_to_source(gen_void_trader_info(&data, None))
+                .await?;
+            return Ok(true);
+        }
+
+        event
+            .send_message_to_source("奸商来了, 正在查询wm价格...".parse_message_chain())
+            .await?;
+        let prices = WF_API
+            .void_trader_prices(platform, &data.inventory, exact_url_name)
+            .await;
+        event
+            .send_message_to_source(gen_void_trader_info(&data, Some(&prices)))
+            .await?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+pub fn module() -> Module {
+    module!("void_trader", "奸商", cmd)
+}
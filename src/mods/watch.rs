@@ -0,0 +1,185 @@
+use itertools::Itertools;
+use proc_qq::{
+    event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
+    MessageSendToSourceTrait, Module,
+};
+
+use crate::db::{item_meta, ITEMS_INDEX};
+use crate::mods::{query_platform, resolve_item, source_group};
+use crate::resolve::normalize;
+use crate::watches::{add_watch, update_watches, watches, PriceWatch, MAX_WATCHES, WATCH_DURATION};
+use crate::wf_api::remaining_time;
+
+const USAGE: &str = "盯价 [平台] [+mod等级] <物品名> <= <价格>: 有游戏中的卖家价格不高于这个时提醒, 如: 盯价 +10 赤毒 <= 30\n\
+    盯价: 查看自己的盯价\n\
+    取消盯价 <序号/物品名/全部>: 取消盯价";
+
+fn watch_info(watch: &PriceWatch) -> String {
+    let mut line = watch.item_name.to_string();
+    if let Some(rank) = watch.mod_rank {
+        line.push_str(&format!(" ({} 级)", rank));
+    }
+    line.push_str(&format!(
+        " {} <= {} 白金, {}提醒, 剩余 {}",
+        watch.platform().name(),
+        watch.max_price,
+        if watch.group.is_some() {
+            "群里@"
+        } else {
+            "私聊"
+        },
+        remaining_time(watch.expiry)
+    ));
+    line
+}
+
+fn watch_list(user: i64) -> anyhow::Result<String> {
+    let watches = watches(user)?
+        .into_iter()
+        .filter(|watch| !watch.is_expired())
+        .collect::<Vec<_>>();
+    Ok(if watches.is_empty() {
+        "你没有盯价".to_owned()
+    } else {
+        format!(
+            "你的盯价:\n{}",
+            watches
+                .iter()
+                .enumerate()
+                .map(|(i, watch)| format!("{}. {}", i + 1, watch_info(watch)))
+                .join("\n")
+        )
+    })
+}
+
+/// 按序号, 物品名或`全部`取消, 返回取消的盯价数.
+fn cancel(user: i64, param: &str) -> anyhow::Result<usize> {
+    let key = normalize(param);
+    update_watches(user, |watches| {
+        watches.retain(|watch| !watch.is_expired());
+        let len = watches.len();
+        if param == "全部" {
+            watches.clear();
+        } else if let Ok(i) = param.parse::<usize>() {
+            if (1..=len).contains(&i) {
+                watches.remove(i - 1);
+            }
+        } else {
+            watches.retain(|watch| normalize(&watch.item_name) != key && watch.url_name != key);
+        }
+        len - watches.len()
+    })
+}
+
+#[event]
+async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
+    let content = event.message_content();
+    let user = event.from_uin();
+
+    if let Some(param) = content.strip_prefix("取消盯价") {
+        let param = param.trim();
+        let reply = if param.is_empty() {
+            USAGE.to_owned()
+        } else {
+            match cancel(user, param)? {
+                0 => format!("没有找到盯价 {}", param),
+                n => format!("已取消 {} 个盯价", n),
+            }
+        };
+        event
+            .send_message_to_source(reply.parse_message_chain())
+            .await?;
+        return Ok(true);
+    }
+
+    let param = match content.strip_prefix("盯价") {
+        Some(param) => param.trim(),
+        None => return Ok(false),
+    };
+    if param.is_empty() {
+        event
+            .send_message_to_source(watch_list(user)?.parse_message_chain())
+            .await?;
+        return Ok(true);
+    }
+
+    let (item, max_price) = match param
+        .split_once("<=")
+        .map(|(item, price)| (item, price.trim().parse::<i32>()))
+    {
+        Some((item, Ok(price))) if price > 0 => (item, price),
+        _ => {
+            event
+                .send_message_to_source(USAGE.parse_message_chain())
+                .await?;
+            return Ok(true);
+        }
+    };
+    let (platform, item) = query_platform(event, item)?;
+    let mut item = item.trim();
+    let mod_rank = match item.strip_prefix('+').and_then(|rest| rest.split_once(' ')) {
+        Some((rank, rest)) => match rank.parse::<i32>() {
+            Ok(rank) => {
+                item = rest.trim();
+                Some(rank)
+            }
+            Err(_) => {
+                event
+                    .send_message_to_source("mod等级必须是数字".parse_message_chain())
+                    .await?;
+                return Ok(true);
+            }
+        },
+        None => None,
+    };
+
+    let url_name = match resolve_item(event, &ITEMS_INDEX, item, "找不到在售物品").await? {
+        Some(url_name) => url_name,
+        None => return Ok(true),
+    };
+    let meta = item_meta(&url_name)?;
+    if let (Some(rank), Some(max_rank)) = (mod_rank, meta.as_ref().and_then(|m| m.max_rank)) {
+        if rank > max_rank {
+            event
+                .send_message_to_source(
+                    format!("{} 最高只有 {} 级", item, max_rank).parse_message_chain(),
+                )
+                .await?;
+            return Ok(true);
+        }
+    }
+    let item_name = meta
+        .map(|meta| meta.item_name)
+        .unwrap_or_else(|| item.into());
+
+    let watch = PriceWatch::new(
+        url_name.into(),
+        item_name,
+        max_price,
+        mod_rank,
+        platform,
+        source_group(event),
+    );
+    let info = watch_info(&watch);
+    let reply = if add_watch(user, watch)? {
+        format!(
+            "已开始盯价, {} 天后过期:\n{}",
+            WATCH_DURATION.whole_days(),
+            info
+        )
+    } else {
+        format!(
+            "最多只能盯 {} 个价格, 先取消一些吧\n{}",
+            MAX_WATCHES,
+            watch_list(user)?
+        )
+    };
+    event
+        .send_message_to_source(reply.parse_message_chain())
+        .await?;
+    Ok(true)
+}
+
+pub fn module() -> Module {
+    module!("watch", "盯价", cmd)
+}
@@ -1,27 +1,495 @@
-use std::sync::Arc;
-
 use compact_str::CompactStr;
 use itertools::Itertools;
-use once_cell::sync::Lazy;
 use proc_qq::{
     event, module, MessageChainParseTrait, MessageContentTrait, MessageEvent,
     MessageSendToSourceTrait, Module,
 };
 
+use crate::aliases::apply_aliases;
+use crate::db::{
+    item_url_name, part_set, riven_attr, riven_attr_by_url_name, riven_attrs_stored, riven_type,
+    set_parts, ITEMS_DB, ITEMS_INDEX, RIVENS_DB, RIVENS_INDEX,
+};
+use crate::mods::{
+    is_group_admin, is_owner, query_platform, reply_api_error, resolve_item, source_group,
+    source_order_filter, source_platform,
+};
+use crate::resolve::{resolve, resolve_strict, Resolved};
+use crate::settings;
 use crate::wf_api::{
-    update_items_db, update_rivens_db, wm_item, wm_riven, OrderType, UserStatus, RIVEN_ATTR,
+    gen_catalog_report, gen_statistics_info, matching_orders, riven_type_name, Order, OrderFilter,
+    OrderType, Platform, Polarity, RivenAttribute, RivenQuery, SaleType, SetPart, UserStatus,
+    NEGATIVE_STATS, WF_API,
 };
 
-static ITEMS_DB: Lazy<Arc<sled::Db>> =
-    Lazy::new(|| Arc::new(sled::open("items_db").expect("ITEMS_DB open err")));
-static RIVENS_DB: Lazy<Arc<sled::Db>> =
-    Lazy::new(|| Arc::new(sled::open("rivens_db").expect("RIVENS_DB open err")));
+/// warframe.market的区域
+const REGIONS: [&str; 11] = [
+    "en", "ru", "ko", "de", "fr", "pt", "zh", "es", "it", "pl", "uk",
+];
+const MAX_ORDER_COUNT: i32 = 20;
+/// `zk`可用的搜索参数, 用于提示
+const RIVEN_OPTIONS: &str = "段位:8-16, 洗练:0-10, 极性:r/盾/-/=, 满级, 一口价/竞拍/直售, 声望>=10";
+
+/// `wm`查询的订单方向
+#[derive(Debug, Copy, Clone)]
+enum OrderMode {
+    Sell,
+    Buy,
+    /// 最高收价和最低卖价的差
+    Spread,
+}
+
+impl OrderMode {
+    /// `收 赤毒`中的`收`, 没写时为卖单.
+    fn split_prefix(param: &str) -> (Self, &str) {
+        let (first, rest) = param.split_once(' ').unwrap_or((param, ""));
+        match first.to_ascii_lowercase().as_str() {
+            "收" | "buy" => (OrderMode::Buy, rest.trim()),
+            "差价" | "spread" => (OrderMode::Spread, rest.trim()),
+            _ => (OrderMode::Sell, param),
+        }
+    }
+}
+
+/// 解析一个订单过滤参数, 如`在线`, `区域:zh`, `声望>=10`, `库存>=5`, `条数:8`.
+/// 不是过滤参数时返回`Ok(false)`, 参数值不对时返回错误提示.
+fn apply_filter_flag(filter: &mut OrderFilter, flag: &str) -> Result<bool, String> {
+    let flag = flag.replace('：', ":");
+    let number = |value: &str, name: &str| {
+        value
+            .parse::<i32>()
+            .map_err(|_| format!("{}必须是数字", name))
+    };
+
+    if flag == "在线" {
+        filter.online = true;
+    } else if flag == "游戏中" {
+        filter.online = false;
+    } else if let Some(region) = flag.strip_prefix("区域:") {
+        filter.region = match region.to_ascii_lowercase().as_str() {
+            "all" | "全部" => None,
+            region if REGIONS.contains(&region) => Some(CompactStr::new(region)),
+            _ => {
+                return Err(format!(
+                    "不认识的区域: {}, 可选: {}, 全部",
+                    region,
+                    REGIONS.join(", ")
+                ))
+            }
+        };
+    } else if let Some(reputation) = flag.strip_prefix("声望>=") {
+        filter.min_reputation = number(reputation, "声望")?;
+    } else if let Some(quantity) = flag.strip_prefix("库存>=") {
+        filter.min_quantity = number(quantity, "库存")?;
+    } else if let Some(count) = flag.strip_prefix("条数:") {
+        filter.count = number(count, "条数")?.clamp(1, MAX_ORDER_COUNT) as usize;
+    } else {
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// `8-16`, `8-`, `-16`或`8`, 返回最小值和最大值.
+fn parse_range(value: &str, name: &str) -> Result<(Option<i32>, Option<i32>), String> {
+    let number = |n: &str| match n.trim() {
+        "" => Ok(None),
+        n => n
+            .parse::<i32>()
+            .map(Some)
+            .map_err(|_| format!("{}必须是数字或范围, 如: {}:8-16", name, name)),
+    };
+    match value.split_once('-') {
+        Some((min, max)) => Ok((number(min)?, number(max)?)),
+        None => {
+            let n = number(value)?;
+            Ok((n, n))
+        }
+    }
+}
+
+/// 解析一个紫卡搜索参数, 如`段位:8-16`, `极性:r`, `满级`, `直售`, `声望>=10`.
+/// 不是搜索参数时返回`Ok(false)`(当作武器名的一部分, 见`split_weapon_name`), 参数名或值不对时返回错误提示.
+fn apply_riven_option(query: &mut RivenQuery, option: &str) -> Result<bool, String> {
+    let option = option.replace('：', ":");
+    let unknown = || format!("不认识的参数: {}\n可用: {}", option, RIVEN_OPTIONS);
+
+    match option.as_str() {
+        "满级" => query.maxed = true,
+        "一口价" => query.sale_type = Some(SaleType::Buyout),
+        "竞拍" => query.sale_type = Some(SaleType::Auction),
+        "直售" => query.sale_type = Some(SaleType::Direct),
+        _ => {
+            if let Some(reputation) = option.strip_prefix("声望>=") {
+                query.min_reputation = reputation
+                    .parse()
+                    .map_err(|_| "声望必须是数字".to_owned())?;
+            } else if let Some((name, value)) = option.split_once(':') {
+                match name {
+                    "段位" => {
+                        (query.mastery_rank_min, query.mastery_rank_max) = parse_range(value, name)?
+                    }
+                    "洗练" => {
+                        (query.re_rolls_min, query.re_rolls_max) = parse_range(value, name)?
+                    }
+                    "极性" => {
+                        query.polarity =
+                            Some(Polarity::from_name(value).ok_or_else(|| {
+                                format!("不认识的极性: {}, 可选: r, 盾, -, =", value)
+                            })?)
+                    }
+                    _ => return Err(unknown()),
+                }
+            } else if option.contains(">=") || option.contains("<=") {
+                return Err(unknown());
+            } else {
+                return Ok(false);
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// `zk`中不是词条和搜索参数的部分按武器名查找. 整体不是武器名时找其中最长的能作为武器名的连续部分,
+/// 返回武器的`url_name`和剩下不认识的部分, 都不是武器名时返回`None`.
+/// `lookup`不能接受错别字(见`resolve_strict`), 否则多出的参数会被当作武器名的错别字吞掉.
+fn split_weapon_name(
+    parts: &[&str],
+    lookup: impl Fn(&str) -> anyhow::Result<Option<String>>,
+) -> anyhow::Result<Option<(String, Vec<String>)>> {
+    for len in (1..=parts.len()).rev() {
+        for start in 0..=parts.len() - len {
+            if let Some(url_name) = lookup(&parts[start..start + len].join(""))? {
+                let rest = parts[..start]
+                    .iter()
+                    .chain(&parts[start + len..])
+                    .map(|part| part.to_string())
+                    .collect();
+                return Ok(Some((url_name, rest)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// 检查词条能否出现在`riven_type`类型的紫卡上, 以及只能为正面/负面的词条.
+fn check_riven_attrs(
+    riven_type: &str,
+    positive: &[RivenAttribute],
+    negative: Option<&RivenAttribute>,
+) -> Result<(), String> {
+    for attr in positive.iter().chain(negative) {
+        if !attr.applies_to(riven_type) {
+            return Err(format!(
+                "{}紫卡上不会有词条: {}",
+                riven_type_name(riven_type),
+                attr.effect
+            ));
+        }
+    }
+    if let Some(attr) = positive.iter().find(|attr| attr.negative_only) {
+        return Err(format!("{} 只能是负面词条", attr.effect));
+    }
+    if let Some(attr) = negative.filter(|attr| attr.positive_only) {
+        return Err(format!("{} 只能是正面词条", attr.effect));
+    }
+    Ok(())
+}
+
+/// 取出参数中的订单过滤参数, 返回剩下的参数.
+fn split_filter_flags(filter: &mut OrderFilter, param: &str) -> Result<String, String> {
+    let mut rest = Vec::new();
+    for token in param.split_whitespace() {
+        if !apply_filter_flag(filter, token)? {
+            rest.push(token);
+        }
+    }
+    Ok(rest.join(" "))
+}
+
+fn filter_info(filter: &OrderFilter) -> String {
+    format!(
+        "{}的玩家, 区域: {}, 声望>={}, 库存>={}, 显示{}条",
+        status_name(filter),
+        filter.region.as_deref().unwrap_or("全部"),
+        filter.min_reputation,
+        filter.min_quantity,
+        filter.count
+    )
+}
+
+fn status_name(filter: &OrderFilter) -> &'static str {
+    if filter.online {
+        "在线"
+    } else {
+        "游戏中"
+    }
+}
+
+fn order_line(order: &Order) -> String {
+    let mut line = match order.order_type {
+        OrderType::Buy => format!(
+            "{name} 收 ${platinum}, 收 {count} 个",
+            name = order.user.ingame_name,
+            platinum = order.platinum,
+            count = order.quantity,
+        ),
+        _ => format!(
+            "{name} 卖 ${platinum}, 库存 {count} 个",
+            name = order.user.ingame_name,
+            platinum = order.platinum,
+            count = order.quantity,
+        ),
+    };
+    if let Some(rank) = order.mod_rank {
+        line.push_str(&format!(" ({} 级)", rank));
+    }
+    if !matches!(order.user.status, UserStatus::InGame) {
+        line.push_str(&format!(" [{}]", order.user.status.chinese()));
+    }
+    line.push('\n');
+    line
+}
+
+fn gen_spread_info(buy: Option<&Order>, sell: Option<&Order>, filter: &OrderFilter) -> String {
+    let price_line = |order: Option<&Order>| match order {
+        Some(order) => format!("${} ({})", order.platinum, order.user.ingame_name),
+        None => "没有".to_owned(),
+    };
+    let mut info = format!(
+        "最高收价: {}\n最低卖价: {}",
+        price_line(buy),
+        price_line(sell)
+    );
+    if let (Some(buy), Some(sell)) = (buy, sell) {
+        info.push_str(&format!("\n差价: ${}", sell.platinum - buy.platinum));
+    }
+    info.push_str(&format!("\n~ 截至{}的买家和卖家", status_name(filter)));
+    info
+}
+
+/// 套装名对应的套装`url_name`, 可以省略"一套", 也可以用部件名.
+fn find_set(name: &str) -> anyhow::Result<Option<String>> {
+    let base = name
+        .trim()
+        .trim_end_matches("套装")
+        .trim_end_matches("一套")
+        .trim_end_matches('套')
+        .trim();
+    for candidate in [
+        name.to_owned(),
+        format!("{}一套", base),
+        format!("{} set", base),
+    ] {
+        if let Some(url_name) = item_url_name(&candidate)? {
+            if set_parts(&url_name)?.is_some() {
+                return Ok(Some(url_name));
+            }
+            if let Some(set) = part_set(&url_name)? {
+                return Ok(Some(set));
+            }
+        }
+    }
+    match resolve(&ITEMS_INDEX, name)? {
+        Resolved::Found(url_name) if set_parts(&url_name)?.is_some() => Ok(Some(url_name)),
+        Resolved::Found(url_name) => Ok(part_set(&url_name)?),
+        Resolved::NotFound(_) => Ok(None),
+    }
+}
+
+fn lowest_sell(orders: &[Order], filter: &OrderFilter) -> Option<i32> {
+    matching_orders(orders, &OrderType::Sell, None, filter)
+        .map(|order| order.platinum)
+        .min()
+}
+
+fn price_or(platinum: Option<i32>, none: &str) -> String {
+    match platinum {
+        Some(platinum) => format!("${}", platinum),
+        None => none.to_owned(),
+    }
+}
+
+/// 买部件和买套装的价格对比, `part_orders`与`parts`一一对应.
+fn gen_set_info(
+    set_name: &str,
+    parts: &[SetPart],
+    part_orders: &[Vec<Order>],
+    set_orders: &[Order],
+    filter: &OrderFilter,
+) -> String {
+    let mut info = format!("{} 的部件:", set_name);
+    // 有部件没有卖家时为None
+    let mut parts_total = Some(0);
+    for (part, orders) in parts.iter().zip(part_orders) {
+        match lowest_sell(orders, filter) {
+            Some(platinum) => {
+                info.push_str(&format!(
+                    "\n  {} x{}: ${} (共 ${})",
+                    part.item_name,
+                    part.quantity,
+                    platinum,
+                    platinum * part.quantity
+                ));
+                parts_total = parts_total.map(|total| total + platinum * part.quantity);
+            }
+            None => {
+                info.push_str(&format!(
+                    "\n  {} x{}: 没有卖家",
+                    part.item_name, part.quantity
+                ));
+                parts_total = None;
+            }
+        }
+    }
+
+    let set_sell = lowest_sell(set_orders, filter);
+    let set_buy = matching_orders(set_orders, &OrderType::Buy, None, filter)
+        .map(|order| order.platinum)
+        .max();
+    info.push_str(&format!(
+        "\n部件合计: {}\n套装最低卖价: {}\n套装最高收价: {}",
+        price_or(parts_total, "有部件没有卖家"),
+        price_or(set_sell, "没有卖家"),
+        price_or(set_buy, "没有买家"),
+    ));
+    if let Some(total) = parts_total {
+        if let Some(sell) = set_sell {
+            info.push_str(&format!("\n买部件按最低价卖套装, 利润: ${}", sell - total));
+        }
+        if let Some(buy) = set_buy {
+            info.push_str(&format!("\n买部件卖给收套装的, 利润: ${}", buy - total));
+        }
+    }
+    info.push_str(&format!("\n~ 截至{}的玩家", status_name(filter)));
+    info
+}
 
 #[event]
 async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
     let content = event.message_content();
-    if content.starts_with("wm") {
-        let mut param = content.trim_start_matches("wm").trim();
+    if let Some(param) = content.strip_prefix("wm统计") {
+        let (platform, param) = query_platform(event, param)?;
+        let item_name = param.to_ascii_lowercase().replace(" ", "");
+        match resolve_item(event, &ITEMS_INDEX, &item_name, "找不到在售物品").await? {
+            None => {}
+            Some(url_name) => match WF_API.wm_statistics(platform, url_name.as_ref()).await {
+                Ok(statistics) => {
+                    event
+                        .send_message_to_source(gen_statistics_info(param.trim(), &statistics))
+                        .await?;
+                }
+                Err(err) => {
+                    reply_api_error(event, "warframe.market", &err).await?;
+                }
+            },
+        }
+        Ok(true)
+    } else if let Some(param) = content.strip_prefix("wm套装") {
+        let (platform, param) = query_platform(event, param)?;
+        let set_url_name = match find_set(&apply_aliases(source_group(event), param)?)? {
+            Some(set_url_name) => set_url_name,
+            None => {
+                event
+                    .send_message_to_source(
+                        format!("找不到套装 {}, 可能需要先update_items_db", param)
+                            .parse_message_chain(),
+                    )
+                    .await?;
+                return Ok(true);
+            }
+        };
+
+        let parts = match set_parts(&set_url_name)? {
+            Some(parts) if !parts.is_empty() => parts,
+            _ => {
+                event
+                    .send_message_to_source(
+                        format!(
+                            "{} 没有套装部件信息, 可能需要先update_items_db",
+                            param.trim()
+                        )
+                        .parse_message_chain(),
+                    )
+                    .await?;
+                return Ok(true);
+            }
+        };
+        // 先请求所有部件, 最后请求套装本身
+        let mut part_orders = Vec::with_capacity(parts.len() + 1);
+        let url_names = parts
+            .iter()
+            .map(|part| part.url_name.to_string())
+            .chain([set_url_name])
+            .collect::<Vec<_>>();
+        for url_name in &url_names {
+            match WF_API.wm_item(platform, url_name).await {
+                Ok(orders) => part_orders.push(orders),
+                Err(err) => {
+                    reply_api_error(event, "warframe.market", &err).await?;
+                    return Ok(true);
+                }
+            }
+        }
+        let set_orders = part_orders.pop().unwrap_or_default();
+
+        let filter = source_order_filter(event)?;
+        event
+            .send_message_to_source(
+                gen_set_info(param, &parts, &part_orders, &set_orders, &filter)
+                    .parse_message_chain(),
+            )
+            .await?;
+        Ok(true)
+    } else if let Some(param) = content.strip_prefix("wm设置") {
+        let reply = match (event.as_group_message(), param.trim()) {
+            (_, "") => format!(
+                "当前wm过滤条件: {}",
+                filter_info(&source_order_filter(event)?)
+            ),
+            (Err(_), _) => {
+                "只能设置群的默认过滤条件, 私聊查询时可以在命令里加参数, 如: wm 在线 赤毒"
+                    .to_owned()
+            }
+            _ if !(is_owner(event)? || is_group_admin(event).await?) => {
+                "只有群管理可以修改本群的wm过滤条件".to_owned()
+            }
+            (Ok(group), "重置") => {
+                settings::reset_order_filter(group.inner.group_code)?;
+                format!("已重置为: {}", filter_info(&OrderFilter::default()))
+            }
+            (Ok(group), param) => {
+                let mut filter = settings::order_filter(group.inner.group_code)?;
+                match split_filter_flags(&mut filter, param) {
+                    Err(err) => err,
+                    Ok(rest) if !rest.is_empty() => format!(
+                        "不认识的参数: {}\n可用: 在线/游戏中, 区域:zh, 声望>=10, 库存>=5, 条数:8",
+                        rest
+                    ),
+                    Ok(_) => {
+                        settings::set_order_filter(group.inner.group_code, &filter)?;
+                        format!("本群的wm过滤条件已设置为: {}", filter_info(&filter))
+                    }
+                }
+            }
+        };
+        event
+            .send_message_to_source(reply.parse_message_chain())
+            .await?;
+        Ok(true)
+    } else if content.starts_with("wm") {
+        let (platform, param) = query_platform(event, content.trim_start_matches("wm"))?;
+        let (mode, param) = OrderMode::split_prefix(param);
+        let mut filter = source_order_filter(event)?;
+        let param = match split_filter_flags(&mut filter, param) {
+            Ok(param) => param,
+            Err(err) => {
+                event
+                    .send_message_to_source(err.parse_message_chain())
+                    .await?;
+                return Ok(true);
+            }
+        };
+        let mut param = param.as_str();
         let mod_lvl = if param.starts_with("+") {
             if let Some((lvl, other)) = param.trim_start_matches("+").split_once(" ") {
                 if let Ok(lvl) = lvl.parse::<i32>() {
@@ -40,119 +508,184 @@ async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
             None
         };
         let item_name = param.to_ascii_lowercase().replace(" ", "");
-        match ITEMS_DB.get(item_name.as_bytes())? {
-            None => {
-                event
-                    .send_message_to_source(
-                        format!("找不到在售物品 {}", item_name).parse_message_chain(),
-                    )
-                    .await?;
-            }
+        match resolve_item(event, &ITEMS_INDEX, &item_name, "找不到在售物品").await? {
+            None => {}
             Some(url_name) => {
-                let url_name = String::from_utf8_lossy(url_name.as_ref());
-                let orders = wm_item(url_name.as_ref()).await?;
-
-                let mut orders_info = String::with_capacity(512);
-
-                orders
-                    .into_iter()
-                    .filter(|order| matches!(order.user.status, UserStatus::InGame))
-                    .filter(|order| matches!(order.order_type, OrderType::Sell))
-                    .filter(|order| order.region == "en")
-                    .filter(|order| order.visible)
-                    .filter(|order| {
-                        if let (Some(lvl), Some(lvl2)) = (mod_lvl, order.mod_rank) {
-                            lvl == lvl2
-                        } else {
-                            true
-                        }
-                    })
-                    .sorted_unstable_by(|l, r| l.platinum.cmp(&r.platinum))
-                    .take(4)
-                    .for_each(|order| {
-                        orders_info.push_str(
-                            format!(
-                                "{name} 卖 ${platinum}, 库存 {count} 个",
-                                name = &order.user.ingame_name,
-                                platinum = order.platinum,
-                                count = order.quantity,
-                            )
-                            .as_str(),
-                        );
-                        if let Some(rank) = order.mod_rank {
-                            orders_info.push_str(format!(" ({} 级)", rank).as_str());
-                        }
-                        orders_info.push('\n');
-                    });
+                let orders = match WF_API.wm_item(platform, url_name.as_ref()).await {
+                    Ok(orders) => orders,
+                    Err(err) => {
+                        reply_api_error(event, "warframe.market", &err).await?;
+                        return Ok(true);
+                    }
+                };
 
+                let reply = match mode {
+                    OrderMode::Sell => format!(
+                        "{}~ 截至{}卖家价格最低前{}条",
+                        matching_orders(&orders, &OrderType::Sell, mod_lvl, &filter)
+                            .sorted_unstable_by_key(|order| order.platinum)
+                            .take(filter.count)
+                            .map(order_line)
+                            .join(""),
+                        status_name(&filter),
+                        filter.count
+                    ),
+                    OrderMode::Buy => format!(
+                        "{}~ 截至{}买家价格最高前{}条",
+                        matching_orders(&orders, &OrderType::Buy, mod_lvl, &filter)
+                            .sorted_unstable_by_key(|order| -order.platinum)
+                            .take(filter.count)
+                            .map(order_line)
+                            .join(""),
+                        status_name(&filter),
+                        filter.count
+                    ),
+                    OrderMode::Spread => gen_spread_info(
+                        matching_orders(&orders, &OrderType::Buy, mod_lvl, &filter)
+                            .max_by_key(|order| order.platinum),
+                        matching_orders(&orders, &OrderType::Sell, mod_lvl, &filter)
+                            .min_by_key(|order| order.platinum),
+                        &filter,
+                    ),
+                };
                 event
-                    .send_message_to_source(
-                        format!("{}~ 截至游戏中卖家价格最低前4条", orders_info).parse_message_chain(),
-                    )
+                    .send_message_to_source(reply.parse_message_chain())
                     .await?;
             }
         }
         Ok(true)
     } else if content.starts_with("zk") {
-        let mut positive_stats = Vec::new();
-        let mut negative_stats = CompactStr::new_inline("");
+        let mut query = RivenQuery::default();
+        let mut name_parts = Vec::new();
+        let mut positive_attrs = Vec::new();
+        let mut negative_attr = None;
 
         let mut params = content.split_whitespace().peekable();
         params.next().unwrap();
+        let platform = match params.peek().and_then(|param| Platform::from_alias(param)) {
+            Some(platform) => {
+                params.next().unwrap();
+                platform
+            }
+            None => source_platform(event)?,
+        };
 
-        loop {
-            if let Some(param) = params.peek() {
-                let mut chs = param.chars().peekable();
-                if let Some('+') = chs.peek() {
-                    chs.next().unwrap();
-                    let attr = CompactStr::from_iter(chs);
-                    if let Some(url_name) = RIVEN_ATTR.get(&attr) {
-                        positive_stats.push(CompactStr::new(url_name));
-                    } else {
-                        event
-                            .send_message_to_source(
-                                format!("找不到词条: {}", attr).parse_message_chain(),
-                            )
-                            .await?;
-                    }
-                } else if let Some('-') = chs.peek() {
-                    chs.next().unwrap();
-                    let attr = CompactStr::from_iter(chs);
-                    if let Some(url_name) = RIVEN_ATTR.get(&attr) {
-                        negative_stats = CompactStr::new(url_name);
-                    } else {
+        let mut negative_special = None;
+        let params = params.collect::<Vec<_>>();
+        let has_attrs = params.iter().any(|param| match param.strip_prefix('-') {
+            Some(attr) => !NEGATIVE_STATS.contains_key(attr),
+            None => param.starts_with('+'),
+        });
+        if has_attrs && !riven_attrs_stored()? {
+            event
+                .send_message_to_source(
+                    "还没有储存紫卡词条, 不能按词条搜索, 需要先update_rivens_db"
+                        .parse_message_chain(),
+                )
+                .await?;
+            return Ok(true);
+        }
+
+        for param in params {
+            if let Some(special) = param
+                .strip_prefix('-')
+                .and_then(|attr| NEGATIVE_STATS.get(attr))
+            {
+                negative_special = Some(*special);
+            } else if let Some(attr) = param.strip_prefix('+') {
+                if let Some(attr) = riven_attr(attr)? {
+                    positive_attrs.push(attr);
+                } else {
+                    event
+                        .send_message_to_source(
+                            format!("找不到词条: {}", attr).parse_message_chain(),
+                        )
+                        .await?;
+                }
+            } else if let Some(attr) = param.strip_prefix('-') {
+                if let Some(attr) = riven_attr(attr)? {
+                    negative_attr = Some(attr);
+                } else {
+                    event
+                        .send_message_to_source(
+                            format!("找不到词条: {}", attr).parse_message_chain(),
+                        )
+                        .await?;
+                }
+            } else {
+                match apply_riven_option(&mut query, param) {
+                    Ok(true) => {}
+                    Ok(false) => name_parts.push(param),
+                    Err(err) => {
                         event
-                            .send_message_to_source(
-                                format!("找不到词条: {}", attr).parse_message_chain(),
-                            )
+                            .send_message_to_source(err.parse_message_chain())
                             .await?;
+                        return Ok(true);
                     }
-                } else {
-                    break;
                 }
-                params.next().unwrap();
-            } else {
-                break;
             }
         }
 
-        let item_name = params.join("");
-        match RIVENS_DB.get(&item_name)? {
-            None => {
+        let item_name = name_parts.join("");
+        let group = source_group(event);
+        let split = split_weapon_name(&name_parts, |name| {
+            Ok(resolve_strict(&RIVENS_INDEX, &apply_aliases(group, name)?)?)
+        })?;
+        let url_name = match split {
+            Some((url_name, rest)) if rest.is_empty() => Some(url_name),
+            Some((_, rest)) => {
+                event
+                    .send_message_to_source(
+                        format!("不认识的参数: {}\n可用: {}", rest.join(" "), RIVEN_OPTIONS)
+                            .parse_message_chain(),
+                    )
+                    .await?;
+                return Ok(true);
+            }
+            None if name_parts.len() > 1 => {
                 event
                     .send_message_to_source(
-                        format!("找不到在售的 {} 紫卡", item_name).parse_message_chain(),
+                        format!(
+                            "找不到在售紫卡的武器, 不认识的参数: {}\n可用: {}",
+                            name_parts.join(" "),
+                            RIVEN_OPTIONS
+                        )
+                        .parse_message_chain(),
                     )
                     .await?;
+                return Ok(true);
             }
+            None => resolve_item(event, &RIVENS_INDEX, &item_name, "找不到在售紫卡的武器").await?,
+        };
+        match url_name {
+            None => {}
             Some(url_name) => {
                 let mut auctions_info = String::with_capacity(1024);
-                let auctions = wm_riven(
-                    String::from_utf8_lossy(url_name.as_ref()).as_ref(),
-                    &positive_stats.join(","),
-                    &negative_stats,
-                )
-                .await?;
+                if let Some(riven_type) = riven_type(&url_name)? {
+                    if let Err(err) =
+                        check_riven_attrs(&riven_type, &positive_attrs, negative_attr.as_ref())
+                    {
+                        event
+                            .send_message_to_source(err.parse_message_chain())
+                            .await?;
+                        return Ok(true);
+                    }
+                }
+                query.weapon_url_name = CompactStr::new(&url_name);
+                query.positive_stats = positive_attrs
+                    .into_iter()
+                    .map(|attr| attr.url_name)
+                    .collect();
+                query.negative_stats = negative_special
+                    .map(CompactStr::new)
+                    .or_else(|| negative_attr.map(|attr| attr.url_name));
+                let auctions = match WF_API.wm_riven(platform, &query).await {
+                    Ok(auctions) => auctions,
+                    Err(err) => {
+                        reply_api_error(event, "warframe.market", &err).await?;
+                        return Ok(true);
+                    }
+                };
 
                 auctions
                     .into_iter()
@@ -169,21 +702,24 @@ async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
                     .for_each(|auction| {
                         auctions_info.push_str(&format!(
                             "{} {} {}段 {}洗 {}级 {}槽 ${}",
-                            &item_name,
-                            &auction.item.name,
+                            item_name,
+                            auction.item.name,
                             auction.item.mastery_level,
                             auction.item.re_rolls,
                             auction.item.mod_rank,
                             auction.item.polarity.nickname(),
                             auction.buyout_price.unwrap_or(auction.starting_price),
                         ));
+                        if !auction.is_direct_sell {
+                            auctions_info.push_str(" (竞拍)");
+                        }
 
                         auction.item.attributes.iter().for_each(|attr| {
-                            let attr_name = RIVEN_ATTR
-                                .entries()
-                                .find(|(_, url_name2)| attr.url_name == url_name2)
-                                .map(|(attr_name, _)| *attr_name)
-                                .unwrap_or(&attr.url_name);
+                            let attr_name = riven_attr_by_url_name(&attr.url_name)
+                                .ok()
+                                .flatten()
+                                .map(|attr| attr.effect)
+                                .unwrap_or_else(|| attr.url_name.clone());
                             auctions_info.push_str(&format!(
                                 "\n  {}{} {}",
                                 if attr.value >= 0f64 { "+" } else { "" },
@@ -203,30 +739,48 @@ async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
             }
         }
         Ok(true)
-    } else if content == "update_items_db" {
-        let num = update_items_db(ITEMS_DB.clone()).await?;
-        event
-            .send_message_to_source(
-                format!(
-                    "成功储存 {} 条数据, 数据库中共有 {} 条数据",
-                    num,
-                    ITEMS_DB.len()
-                )
-                .parse_message_chain(),
-            )
-            .await?;
-        Ok(true)
-    } else if content == "update_rivens_db" {
-        let num = update_rivens_db(RIVENS_DB.clone()).await?;
+    } else if content == "update_items_db" || content == "update_rivens_db" {
+        if !is_owner(event)? {
+            event
+                .send_message_to_source(format!("只有主人可以{}", content).parse_message_chain())
+                .await?;
+            return Ok(true);
+        }
+
+        let reply = if content == "update_items_db" {
+            let update = WF_API.update_items_db(ITEMS_DB.clone()).await?;
+            tokio::spawn(async {
+                match WF_API.update_item_meta(ITEMS_DB.clone()).await {
+                    Ok(num) => tracing::info!("updated meta of {} items", num),
+                    Err(err) => tracing::error!("update item meta error: {}", err),
+                }
+            });
+            let mut reply = format!(
+                "成功储存 {} 条数据, 数据库中共有 {} 条数据, 物品的详细信息在后台更新",
+                update.items,
+                ITEMS_DB.len()
+            );
+            if let Some(version) = &update.version {
+                reply.push('\n');
+                reply.push_str(&gen_catalog_report("物品", version));
+            }
+            reply
+        } else {
+            let update = WF_API.update_rivens_db(RIVENS_DB.clone()).await?;
+            let mut reply = format!(
+                "成功储存 {} 条数据和 {} 个词条, 数据库中共有 {} 条数据",
+                update.rivens,
+                update.attributes,
+                RIVENS_DB.len()
+            );
+            if let Some(version) = &update.version {
+                reply.push('\n');
+                reply.push_str(&gen_catalog_report("紫卡武器", version));
+            }
+            reply
+        };
         event
-            .send_message_to_source(
-                format!(
-                    "成功储存 {} 条数据, 数据库中共有 {} 条数据",
-                    num,
-                    RIVENS_DB.len()
-                )
-                .parse_message_chain(),
-            )
+            .send_message_to_source(reply.parse_message_chain())
             .await?;
         Ok(true)
     } else {
@@ -237,3 +791,90 @@ async fn cmd(event: &MessageEvent) -> anyhow::Result<bool> {
 pub fn module() -> Module {
     module!("arbitration", "仲裁", cmd)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::resolve::NameIndex;
+
+    fn weapons() -> NameIndex {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        for (name, url_name) in [
+            ("绝路", "rubico"),
+            ("绝路prime", "rubico_prime"),
+            ("kuvabramma", "kuva_bramma"),
+        ] {
+            db.insert(name, url_name).unwrap();
+        }
+        NameIndex::new(Arc::new(db))
+    }
+
+    fn split(names: &NameIndex, parts: &[&str]) -> Option<(String, Vec<String>)> {
+        split_weapon_name(parts, |name| Ok(resolve_strict(names, name)?)).unwrap()
+    }
+
+    #[test]
+    fn split_known_weapon_and_unknown_token() {
+        let names = weapons();
+        assert_eq!(
+            split(&names, &["绝路", "x"]),
+            Some(("rubico".to_owned(), vec!["x".to_owned()]))
+        );
+        assert_eq!(
+            split(&names, &["y", "绝路prime"]),
+            Some(("rubico_prime".to_owned(), vec!["y".to_owned()]))
+        );
+    }
+
+    #[test]
+    fn split_whole_weapon_name() {
+        let names = weapons();
+        assert_eq!(
+            split(&names, &["kuva", "bramma"]),
+            Some(("kuva_bramma".to_owned(), Vec::new()))
+        );
+    }
+
+    #[test]
+    fn riven_options() {
+        let mut query = RivenQuery::default();
+        for option in ["段位:8-12", "洗练：-5", "极性:r", "满级", "直售", "声望>=5"] {
+            assert_eq!(
+                apply_riven_option(&mut query, option),
+                Ok(true),
+                "{}",
+                option
+            );
+        }
+        assert_eq!(
+            (query.mastery_rank_min, query.mastery_rank_max),
+            (Some(8), Some(12))
+        );
+        assert_eq!((query.re_rolls_min, query.re_rolls_max), (None, Some(5)));
+        assert_eq!(query.polarity, Some(Polarity::Madurai));
+        assert!(query.maxed);
+        assert_eq!(query.sale_type, Some(SaleType::Direct));
+        assert_eq!(query.min_reputation, 5);
+    }
+
+    #[test]
+    fn riven_option_errors() {
+        let mut query = RivenQuery::default();
+        assert_eq!(apply_riven_option(&mut query, "绝路"), Ok(false));
+        for option in ["段位:x", "极性:y", "颜色:红", "声望>=a", "等级>=3"] {
+            assert!(
+                apply_riven_option(&mut query, option).is_err(),
+                "{}",
+                option
+            );
+        }
+    }
+
+    #[test]
+    fn split_rejects_typos() {
+        let names = weapons();
+        assert_eq!(split(&names, &["绝陆"]), None);
+    }
+}
@@ -0,0 +1,377 @@
+//! 物品名的模糊查找, wm和zk共用. 数据库的key为小写去空格的名字, value为`url_name`.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use pinyin::ToPinyin;
+
+/// `你是不是要找`最多列出的名字数
+const MAX_SUGGESTIONS: usize = 5;
+
+/// 部件的常用简称, 按后缀替换, 同一后缀长的在前.
+const PART_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("总图", "蓝图"),
+    ("头部", "头部神经光元"),
+    ("头", "头部神经光元"),
+    ("机体", "机体"),
+    ("系统", "系统"),
+    ("一套", "一套"),
+    ("套装", "一套"),
+    ("套", "一套"),
+];
+
+pub enum Resolved {
+    Found(String),
+    /// 没有唯一的匹配, 附带最接近的几个名字
+    NotFound(Vec<String>),
+}
+
+/// 匹配程度, 越小越好.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Match {
+    Exact,
+    Prefix,
+    /// 编辑距离
+    Typo(usize),
+}
+
+/// 数据库key的格式, 不区分大小写和空格.
+pub fn normalize(name: &str) -> String {
+    name.to_ascii_lowercase().replace(' ', "")
+}
+
+/// 展开简称: 部件名按`PART_ABBREVIATIONS`, 部件名前(或结尾)的`p`在展开后的名字存在时为`prime`.
+fn expand_abbreviations(db: &sled::Tree, key: &str) -> sled::Result<String> {
+    let (base, part) = PART_ABBREVIATIONS
+        .iter()
+        .find_map(|(abbr, full)| key.strip_suffix(abbr).map(|base| (base, *full)))
+        .unwrap_or((key, ""));
+    if let Some(base) = base.strip_suffix('p') {
+        let primed = format!("{}prime{}", base, part);
+        if db.contains_key(&primed)? {
+            return Ok(primed);
+        }
+    }
+    Ok(format!("{}{}", base, part))
+}
+
+/// 模糊查找用的一条索引, 预先算好拼音.
+struct IndexEntry {
+    name: String,
+    url_name: String,
+    /// 英文输入比较的形式: 去掉`_`的`url_name`, 全拼和首字母
+    ascii_forms: [String; 3],
+}
+
+/// 每次物品目录更新时加一, 与`NameIndex`建立模糊查找索引时的值不同时重建.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 名字索引: 数据库的默认tree(key为`normalize`后的名字, value为`url_name`)和缓存的模糊查找索引.
+pub struct NameIndex {
+    db: Arc<sled::Db>,
+    /// 建立时的`GENERATION`和模糊查找索引
+    fuzzy: Mutex<Option<(u64, Arc<Vec<IndexEntry>>)>>,
+}
+
+impl NameIndex {
+    pub fn new(db: Arc<sled::Db>) -> Self {
+        NameIndex {
+            db,
+            fuzzy: Mutex::new(None),
+        }
+    }
+
+    fn fuzzy(&self) -> sled::Result<Arc<Vec<IndexEntry>>> {
+        let generation = GENERATION.load(Ordering::Acquire);
+        if let Some((built, index)) = self.fuzzy.lock().unwrap().as_ref() {
+            if *built == generation {
+                return Ok(index.clone());
+            }
+        }
+
+        let mut entries = Vec::new();
+        for entry in self.db.iter() {
+            let (name, url_name) = entry?;
+            let name = String::from_utf8_lossy(name.as_ref()).into_owned();
+            let url_name = String::from_utf8_lossy(url_name.as_ref()).into_owned();
+            let (full, initials) = pinyin_forms(&name);
+            entries.push(IndexEntry {
+                ascii_forms: [url_name.replace('_', ""), full, initials],
+                name,
+                url_name,
+            });
+        }
+        let index = Arc::new(entries);
+        *self.fuzzy.lock().unwrap() = Some((generation, index.clone()));
+        Ok(index)
+    }
+}
+
+/// 数据库的名字变了, 下次模糊查找时重建所有`NameIndex`的索引.
+pub fn invalidate_indexes() {
+    GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// 全拼和首字母, 不是汉字的字符原样保留.
+fn pinyin_forms(name: &str) -> (String, String) {
+    let mut full = String::with_capacity(name.len() * 2);
+    let mut initials = String::with_capacity(name.len());
+    name.chars()
+        .zip(name.to_pinyin())
+        .for_each(|(ch, pinyin)| match pinyin {
+            Some(pinyin) => {
+                full.push_str(pinyin.plain());
+                initials.push_str(pinyin.first_letter());
+            }
+            None => {
+                full.push(ch);
+                initials.push(ch);
+            }
+        });
+    (full, initials)
+}
+
+/// `key`与物品的匹配程度. 英文输入还会与拼音和`url_name`(英文名)比较.
+fn match_name(key: &str, entry: &IndexEntry) -> Option<Match> {
+    let forms = if key.is_ascii() {
+        std::iter::once(&entry.name)
+            .chain(&entry.ascii_forms)
+            .collect::<Vec<_>>()
+    } else {
+        vec![&entry.name]
+    };
+
+    if forms.iter().any(|form| *form == key) {
+        return Some(Match::Exact);
+    }
+    if forms.iter().any(|form| form.starts_with(key)) {
+        return Some(Match::Prefix);
+    }
+    let max_distance = (key.chars().count() / 3).max(1);
+    forms
+        .iter()
+        .map(|form| strsim::levenshtein(key, form))
+        .min()
+        .filter(|distance| *distance <= max_distance)
+        .map(Match::Typo)
+}
+
+/// 名字以`key`开头的物品, 用sled的前缀查找, 不用遍历.
+fn prefix_matches(db: &sled::Tree, key: &str) -> sled::Result<Vec<(Match, String, String)>> {
+    db.scan_prefix(key)
+        .map(|entry| {
+            let (name, url_name) = entry?;
+            Ok((
+                Match::Prefix,
+                String::from_utf8_lossy(name.as_ref()).into_owned(),
+                String::from_utf8_lossy(url_name.as_ref()).into_owned(),
+            ))
+        })
+        .collect()
+}
+
+/// 按完整名, 展开简称后的名字, 前缀, 拼音/首字母, 英文名和编辑距离依次查找.
+/// 前缀用sled的前缀查找, 没有结果时才遍历(缓存的)索引比较拼音, 英文名和编辑距离.
+/// 最好的匹配唯一(编辑距离不超过1)时返回它的`url_name`, 否则返回最接近的几个名字.
+pub fn resolve(names: &NameIndex, name: &str) -> sled::Result<Resolved> {
+    Ok(match find(names, name, Match::Typo(1))? {
+        Ok((_, url_name)) => Resolved::Found(url_name),
+        Err(suggestions) => Resolved::NotFound(suggestions),
+    })
+}
+
+/// 同`resolve`, 但返回唯一匹配的物品名(数据库的key), 找不到时为`None`.
+pub fn resolve_name(names: &NameIndex, name: &str) -> sled::Result<Option<String>> {
+    Ok(find(names, name, Match::Typo(1))?
+        .ok()
+        .map(|(name, _)| name))
+}
+
+/// 同`resolve`, 但不接受错别字(编辑距离)匹配, 返回唯一匹配的`url_name`.
+/// 用于从多个参数中分出物品名, 避免把多出的参数当作错别字吞掉.
+pub fn resolve_strict(names: &NameIndex, name: &str) -> sled::Result<Option<String>> {
+    Ok(find(names, name, Match::Prefix)?
+        .ok()
+        .map(|(_, url_name)| url_name))
+}
+
+/// 同`resolve`, 但只接受完整名(或拼音, 英文名)相同的唯一匹配, 返回它的`url_name`.
+/// 用于外部数据中的物品名, 不在数据库中的物品不能匹配到别的物品上.
+pub fn resolve_exact(names: &NameIndex, name: &str) -> sled::Result<Option<String>> {
+    Ok(find(names, name, Match::Exact)?
+        .ok()
+        .map(|(_, url_name)| url_name))
+}
+
+/// 唯一匹配的`(物品名, url_name)`, 或最接近的几个名字.
+/// 只接受不差于`worst`的匹配.
+fn find(
+    names: &NameIndex,
+    name: &str,
+    worst: Match,
+) -> sled::Result<Result<(String, String), Vec<String>>> {
+    let db: &sled::Tree = &names.db;
+    let key = normalize(name);
+    if key.is_empty() {
+        return Ok(Err(Vec::new()));
+    }
+    let expanded = expand_abbreviations(db, &key)?;
+    for key in [key.clone(), expanded.clone()] {
+        if let Some(url_name) = db.get(&key)? {
+            return Ok(Ok((
+                key,
+                String::from_utf8_lossy(url_name.as_ref()).into_owned(),
+            )));
+        }
+    }
+
+    let mut matches = Vec::new();
+    if worst >= Match::Prefix {
+        matches = prefix_matches(db, &key)?;
+        if expanded != key {
+            matches.extend(prefix_matches(db, &expanded)?);
+        }
+    }
+    if matches.is_empty() {
+        matches = names
+            .fuzzy()?
+            .iter()
+            .filter_map(|entry| {
+                [&key, &expanded]
+                    .into_iter()
+                    .filter_map(|key| match_name(key, entry))
+                    .min()
+                    .map(|m| (m, entry.name.clone(), entry.url_name.clone()))
+            })
+            .collect();
+    }
+    matches.sort_by(|(l, l_name, _), (r, r_name, _)| {
+        l.cmp(r)
+            .then_with(|| l_name.chars().count().cmp(&r_name.chars().count()))
+            .then_with(|| l_name.cmp(r_name))
+    });
+    matches.dedup_by(|(_, l, _), (_, r, _)| l == r);
+    let unique_best = match matches.as_slice() {
+        [(best, ..)] => *best <= worst,
+        [(best, ..), (second, ..), ..] => *best <= worst && best < second,
+        [] => false,
+    };
+    if unique_best {
+        let (_, name, url_name) = matches.swap_remove(0);
+        return Ok(Ok((name, url_name)));
+    }
+
+    Ok(Err(matches
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name, _)| name)
+        .collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> NameIndex {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        for (name, url_name) in [
+            ("voltprime一套", "volt_prime_set"),
+            ("voltprime蓝图", "volt_prime_blueprint"),
+            ("voltprime头部神经光元", "volt_prime_neuroptics"),
+            ("赤毒", "virulent_scourge"),
+            ("sharp", "sharp"),
+        ] {
+            db.insert(name, url_name).unwrap();
+        }
+        NameIndex::new(Arc::new(db))
+    }
+
+    fn found(names: &NameIndex, name: &str) -> Option<String> {
+        match resolve(names, name).unwrap() {
+            Resolved::Found(url_name) => Some(url_name),
+            Resolved::NotFound(_) => None,
+        }
+    }
+
+    #[test]
+    fn expand_prime_only_when_it_exists() {
+        let names = names();
+        assert_eq!(
+            expand_abbreviations(&names.db, "voltp头").unwrap(),
+            "voltprime头部神经光元"
+        );
+        assert_eq!(
+            expand_abbreviations(&names.db, "voltp套").unwrap(),
+            "voltprime一套"
+        );
+        assert_eq!(expand_abbreviations(&names.db, "voltp").unwrap(), "voltp");
+        assert_eq!(expand_abbreviations(&names.db, "sharp").unwrap(), "sharp");
+    }
+
+    #[test]
+    fn resolve_exact_and_abbreviations() {
+        let names = names();
+        assert_eq!(
+            found(&names, "Volt Prime 一套").as_deref(),
+            Some("volt_prime_set")
+        );
+        assert_eq!(
+            found(&names, "voltp头").as_deref(),
+            Some("volt_prime_neuroptics")
+        );
+        assert_eq!(
+            found(&names, "voltp总图").as_deref(),
+            Some("volt_prime_blueprint")
+        );
+    }
+
+    #[test]
+    fn resolve_prefix_pinyin_and_english() {
+        let names = names();
+        assert_eq!(found(&names, "赤").as_deref(), Some("virulent_scourge"));
+        assert_eq!(found(&names, "chidu").as_deref(), Some("virulent_scourge"));
+        assert_eq!(found(&names, "cd").as_deref(), Some("virulent_scourge"));
+        assert_eq!(
+            found(&names, "virulent scourge").as_deref(),
+            Some("virulent_scourge")
+        );
+    }
+
+    #[test]
+    fn resolve_ambiguous_prefix_suggests_shortest_first() {
+        let names = names();
+        match resolve(&names, "voltprime").unwrap() {
+            Resolved::Found(url_name) => panic!("unexpected match {}", url_name),
+            Resolved::NotFound(suggestions) => assert_eq!(
+                suggestions,
+                ["voltprime一套", "voltprime蓝图", "voltprime头部神经光元"]
+            ),
+        }
+    }
+
+    #[test]
+    fn typos_only_in_fuzzy_resolve() {
+        let names = names();
+        assert_eq!(found(&names, "赤读").as_deref(), Some("virulent_scourge"));
+        assert_eq!(resolve_strict(&names, "赤读").unwrap(), None);
+        assert_eq!(
+            resolve_strict(&names, "赤").unwrap().as_deref(),
+            Some("virulent_scourge")
+        );
+        assert_eq!(resolve_exact(&names, "赤").unwrap(), None);
+        assert_eq!(
+            resolve_exact(&names, "chidu").unwrap().as_deref(),
+            Some("virulent_scourge")
+        );
+        assert_eq!(resolve_name(&names, "赤").unwrap().as_deref(), Some("赤毒"));
+    }
+
+    #[test]
+    fn index_rebuilt_after_invalidate() {
+        let names = names();
+        assert_eq!(found(&names, "rubico"), None);
+        names.db.insert("绝路", "rubico").unwrap();
+        invalidate_indexes();
+        assert_eq!(found(&names, "rubico").as_deref(), Some("rubico"));
+    }
+}
@@ -0,0 +1,61 @@
+//! 群设置, 储存在sled中, 每项设置一个tree, key为群号.
+
+use crate::db::SETTINGS_DB;
+use crate::wf_api::{OrderFilter, Platform};
+
+const PLATFORM_TREE: &str = "platform";
+const ORDER_FILTER_TREE: &str = "order_filter";
+
+/// 群的默认平台, 没有设置过为PC.
+pub fn platform(group: i64) -> sled::Result<Platform> {
+    Ok(SETTINGS_DB
+        .open_tree(PLATFORM_TREE)?
+        .get(group.to_be_bytes())?
+        .and_then(|value| Platform::from_alias(&String::from_utf8_lossy(value.as_ref())))
+        .unwrap_or(Platform::Pc))
+}
+
+/// 所有群设置过的平台, 加上默认的PC.
+pub fn platforms() -> sled::Result<Vec<Platform>> {
+    let mut platforms = vec![Platform::Pc];
+    for value in SETTINGS_DB.open_tree(PLATFORM_TREE)?.iter().values() {
+        if let Some(platform) = Platform::from_alias(&String::from_utf8_lossy(value?.as_ref())) {
+            if !platforms.contains(&platform) {
+                platforms.push(platform);
+            }
+        }
+    }
+    Ok(platforms)
+}
+
+pub fn set_platform(group: i64, platform: Platform) -> sled::Result<()> {
+    SETTINGS_DB
+        .open_tree(PLATFORM_TREE)?
+        .insert(group.to_be_bytes(), platform.worldstate())?;
+    Ok(())
+}
+
+/// 群的wm订单过滤条件, 没有设置过为`OrderFilter::default()`.
+pub fn order_filter(group: i64) -> anyhow::Result<OrderFilter> {
+    match SETTINGS_DB
+        .open_tree(ORDER_FILTER_TREE)?
+        .get(group.to_be_bytes())?
+    {
+        Some(value) => Ok(serde_json::from_slice(value.as_ref())?),
+        None => Ok(OrderFilter::default()),
+    }
+}
+
+pub fn set_order_filter(group: i64, filter: &OrderFilter) -> anyhow::Result<()> {
+    SETTINGS_DB
+        .open_tree(ORDER_FILTER_TREE)?
+        .insert(group.to_be_bytes(), serde_json::to_vec(filter)?)?;
+    Ok(())
+}
+
+pub fn reset_order_filter(group: i64) -> sled::Result<()> {
+    SETTINGS_DB
+        .open_tree(ORDER_FILTER_TREE)?
+        .remove(group.to_be_bytes())?;
+    Ok(())
+}
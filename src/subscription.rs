@@ -0,0 +1,162 @@
+//! 群对各类提醒的订阅, 储存在sled中, 每种提醒一个tree, key为群号, value为以`\n`分割的过滤条件.
+//! 没有过滤条件代表订阅全部.
+
+use compact_str::CompactStr;
+use itertools::Itertools;
+
+use crate::db::SUBSCRIPTION_DB;
+use crate::wf_api::CycleAlert;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topic {
+    Fissure,
+    Sortie,
+    ArchonHunt,
+    VoidTrader,
+    Cycle,
+    Bounty,
+    Invasion,
+    WeeklyReset,
+}
+
+impl Topic {
+    pub const ALL: &'static [Topic] = &[
+        Topic::Fissure,
+        Topic::Sortie,
+        Topic::ArchonHunt,
+        Topic::VoidTrader,
+        Topic::Cycle,
+        Topic::Bounty,
+        Topic::Invasion,
+        Topic::WeeklyReset,
+    ];
+
+    fn tree_name(&self) -> &'static str {
+        match self {
+            Topic::Fissure => "fissure",
+            Topic::Sortie => "sortie",
+            Topic::ArchonHunt => "archon_hunt",
+            Topic::VoidTrader => "void_trader",
+            Topic::Cycle => "cycle",
+            Topic::Bounty => "bounty",
+            Topic::Invasion => "invasion",
+            Topic::WeeklyReset => "weekly_reset",
+        }
+    }
+
+    pub fn chinese(&self) -> &'static str {
+        match self {
+            Topic::Fissure => "裂缝",
+            Topic::Sortie => "突击",
+            Topic::ArchonHunt => "执刑官",
+            Topic::VoidTrader => "奸商",
+            Topic::Cycle => "周期",
+            Topic::Bounty => "赏金",
+            Topic::Invasion => "入侵",
+            Topic::WeeklyReset => "周常",
+        }
+    }
+
+    pub fn from_chinese(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|t| t.chinese() == name)
+    }
+
+    /// 检查并规范化过滤条件, 不合法时返回给用户的提示.
+    pub fn normalize_filter(&self, filter: Option<&str>) -> Result<Option<CompactStr>, String> {
+        match (self, filter) {
+            (Topic::Fissure | Topic::Invasion, filter) => Ok(filter.map(CompactStr::new)),
+            (Topic::Bounty, Some(filter)) => Ok(Some(CompactStr::new(filter))),
+            (Topic::Bounty, None) => {
+                Err("格式: 订阅 赏金 <想要的奖励>, 如: 订阅 赏金 陈旧的赛诺".to_owned())
+            }
+            (Topic::Cycle, Some(filter)) => match CycleAlert::parse(filter) {
+                Some(alert) => Ok(Some(CompactStr::new(alert.to_filter()))),
+                None => Err(CYCLE_FILTER_HINT.to_owned()),
+            },
+            (Topic::Cycle, None) => Err(CYCLE_FILTER_HINT.to_owned()),
+            (Topic::Sortie | Topic::ArchonHunt | Topic::VoidTrader | Topic::WeeklyReset, None) => {
+                Ok(None)
+            }
+            (
+                Topic::Sortie | Topic::ArchonHunt | Topic::VoidTrader | Topic::WeeklyReset,
+                Some(_),
+            ) => Err(format!("{}提醒不支持过滤条件", self.chinese())),
+        }
+    }
+
+    fn tree(&self) -> sled::Result<sled::Tree> {
+        SUBSCRIPTION_DB.open_tree(self.tree_name())
+    }
+}
+
+const CYCLE_FILTER_HINT: &str =
+    "格式: 订阅 周期 <地区> <状态> [提前分钟数], 如: 订阅 周期 山谷 温暖 5";
+
+fn decode_filters(value: &[u8]) -> Vec<CompactStr> {
+    String::from_utf8_lossy(value)
+        .split('\n')
+        .filter(|f| !f.is_empty())
+        .map(CompactStr::new)
+        .collect()
+}
+
+/// 订阅了`topic`的所有群和它们的过滤条件.
+pub fn subscribers(topic: Topic) -> sled::Result<Vec<(i64, Vec<CompactStr>)>> {
+    topic
+        .tree()?
+        .iter()
+        .map(|kv| {
+            let (group, filters) = kv?;
+            let group = i64::from_be_bytes(group.as_ref().try_into().unwrap_or_default());
+            Ok((group, decode_filters(&filters)))
+        })
+        .collect()
+}
+
+pub fn filters(topic: Topic, group: i64) -> sled::Result<Option<Vec<CompactStr>>> {
+    Ok(topic
+        .tree()?
+        .get(group.to_be_bytes())?
+        .map(|filters| decode_filters(&filters)))
+}
+
+/// 订阅, `filter`不为空时追加一个过滤条件, 为空时清除已有的过滤条件(订阅全部).
+pub fn subscribe(topic: Topic, group: i64, filter: Option<&str>) -> sled::Result<()> {
+    let filters = match filter {
+        Some(filter) => {
+            let mut filters = filters(topic, group)?.unwrap_or_default();
+            if !filters.iter().any(|f| f.as_str() == filter) {
+                filters.push(CompactStr::new(filter));
+            }
+            filters
+        }
+        None => Vec::new(),
+    };
+    topic
+        .tree()?
+        .insert(group.to_be_bytes(), filters.iter().join("\n").as_bytes())?;
+    SUBSCRIPTION_DB.flush()?;
+    Ok(())
+}
+
+/// 退订, `filter`不为空时只删除这个过滤条件. 返回是否有变化.
+pub fn unsubscribe(topic: Topic, group: i64, filter: Option<&str>) -> sled::Result<bool> {
+    let tree = topic.tree()?;
+    let changed = match filter {
+        None => tree.remove(group.to_be_bytes())?.is_some(),
+        Some(filter) => match filters(topic, group)? {
+            Some(mut filters) if filters.iter().any(|f| f.as_str() == filter) => {
+                filters.retain(|f| f.as_str() != filter);
+                if filters.is_empty() {
+                    tree.remove(group.to_be_bytes())?;
+                } else {
+                    tree.insert(group.to_be_bytes(), filters.iter().join("\n").as_bytes())?;
+                }
+                true
+            }
+            _ => false,
+        },
+    };
+    SUBSCRIPTION_DB.flush()?;
+    Ok(changed)
+}
@@ -1,95 +1,767 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::wf_api::ArbitrationLevel;
+use crate::history;
+use crate::settings;
+use crate::subscription::{self, Topic};
+use crate::watches::{self, PriceWatch};
+use crate::wf_api::{
+    ArbitrationLevel, BountySyndicate, CycleAlert, CycleState, Platform, World,
+    DEFAULT_INVASION_WATCH,
+};
 use compact_str::CompactStr;
+use itertools::Itertools;
+use proc_qq::re_exports::ricq::msg::elem::{At, Text};
+use proc_qq::re_exports::ricq::msg::MessageChain;
 use proc_qq::re_exports::ricq::Client;
 use proc_qq::MessageChainParseTrait;
 use time::OffsetDateTime;
 
-pub fn eidolon(client: Arc<Client>) {
+/// 群设置的平台, 读取失败时当作PC.
+fn group_platform(group: i64) -> Platform {
+    settings::platform(group).unwrap_or_else(|err| {
+        tracing::error!("platform setting of {} error: {}", group, err);
+        Platform::Pc
+    })
+}
+
+/// 按群设置的平台把订阅了`topic`的群分组, 没有群订阅的平台不会出现.
+fn subscribers_by_platform(topic: Topic) -> HashMap<Platform, Vec<(i64, Vec<CompactStr>)>> {
+    match subscription::subscribers(topic) {
+        Ok(targets) => targets
+            .into_iter()
+            .into_group_map_by(|(target, _)| group_platform(*target)),
+        Err(err) => {
+            tracing::error!("{:?} subscribers error: {}", topic, err);
+            HashMap::new()
+        }
+    }
+}
+
+/// 各地区周期切换前提醒订阅的群, 每个周期只提醒一次.
+/// `.env`中的`eidolon_notice`相当于订阅了`希图斯 黑夜 10分钟`.
+pub fn cycle(client: Arc<Client>) {
     tokio::spawn(async move {
-        let mut last_id = CompactStr::new("");
+        let eidolon_alert = CycleAlert {
+            world: World::Cetus,
+            state: CycleState::Night,
+            minutes: 10,
+        };
+        // (群, 过滤条件, 周期结束时间)
+        let mut notified: HashSet<(i64, CompactStr, OffsetDateTime)> = HashSet::new();
+
         let mut timer = tokio::time::interval(Duration::from_secs(30));
         loop {
             timer.tick().await;
 
-            match crate::wf_api::cetus_cycle().await {
-                Ok(data) => {
-                    if data.id == last_id {
-                        continue
+            let mut alerts = match subscription::subscribers(Topic::Cycle) {
+                Ok(targets) => targets
+                    .into_iter()
+                    .flat_map(|(target, filters)| {
+                        filters.into_iter().filter_map(move |filter| {
+                            CycleAlert::parse(&filter).map(|alert| (target, filter, alert))
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+                Err(err) => {
+                    tracing::error!("cycle subscribers error: {}", err);
+                    Vec::new()
+                }
+            };
+            dotenv::var("eidolon_notice")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|x| x.parse().ok())
+                .for_each(|target| {
+                    alerts.push((
+                        target,
+                        CompactStr::new("eidolon_notice"),
+                        eidolon_alert.clone(),
+                    ))
+                });
+            let alerts = alerts
+                .into_iter()
+                .map(|(target, filter, alert)| (target, group_platform(target), filter, alert))
+                .collect::<Vec<_>>();
+
+            let watched = alerts
+                .iter()
+                .map(|(_, platform, _, alert)| (*platform, alert.world))
+                .unique()
+                .collect::<Vec<_>>();
+            for (platform, world) in watched {
+                let data = match crate::wf_api::WF_API.world_cycle(platform, world).await {
+                    Ok(data) => data,
+                    Err(err) => {
+                        tracing::error!("{:?} {:?} cycle timing error: {}", platform, world, err);
+                        continue;
+                    }
+                };
+
+                for (target, _, filter, alert) in alerts
+                    .iter()
+                    .filter(|(_, p, _, a)| *p == platform && a.world == world)
+                {
+                    if !alert.triggered(&data)
+                        || !notified.insert((*target, filter.clone(), data.expiry))
+                    {
+                        continue;
+                    }
+
+                    let message = if filter.as_str() == "eidolon_notice" {
+                        "3傻还有10分钟. 有人带我吗, 我打碎片位插碎片贼快".to_owned()
                     } else {
-                        // secs
-                        let remaining = (data.expiry - OffsetDateTime::now_utc()).whole_seconds();
-                        if remaining < 700 && data.is_day {
-                            let targets = dotenv::var("eidolon_notice")
-                                .unwrap_or_default()
-                                .split(",")
-                                .map(|x| x.parse().unwrap())
-                                .collect::<Vec<i64>>();
-
-                            for target in targets {
-                                client
-                                    .send_group_message(
-                                        target,
-                                        "3傻还有10分钟. 有人带我吗, 我打碎片位插碎片贼快"
-                                            .parse_message_chain(),
-                                    )
-                                    .await
-                                    .unwrap();
+                        format!(
+                            "{}还有 {} 分钟{}",
+                            world.chinese(),
+                            (data.expiry - OffsetDateTime::now_utc()).whole_minutes() + 1,
+                            alert.state.chinese()
+                        )
+                    };
+                    if let Err(err) = client
+                        .send_group_message(*target, message.parse_message_chain())
+                        .await
+                    {
+                        tracing::error!("cycle notice to {} error: {}", target, err);
+                    }
+                }
+            }
+
+            let now = OffsetDateTime::now_utc();
+            notified.retain(|(_, _, expiry)| *expiry > now);
+        }
+    });
+}
+
+/// 仲裁是好图时通知`.env`中`arbitration_notice`的群, 看到的每个仲裁都记录到历史中.
+/// 没有群的PC平台也会记录.
+pub fn arbitration(client: Arc<Client>) {
+    tokio::spawn(async move {
+        let mut last_ids: HashMap<Platform, CompactStr> = HashMap::new();
+
+        let mut timer = tokio::time::interval(Duration::from_secs(30));
+        loop {
+            timer.tick().await;
+
+            let mut targets = dotenv::var("arbitration_notice")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|x| x.parse().ok())
+                .into_group_map_by(|target: &i64| group_platform(*target));
+            targets.entry(Platform::Pc).or_default();
+
+            for (platform, targets) in targets {
+                let data = match crate::wf_api::WF_API.arbitration(platform).await {
+                    Ok(a) => a,
+                    Err(err) => {
+                        tracing::error!("{:?} arbitration timing error: {}", platform, err);
+                        continue;
+                    }
+                };
+                let last_id = last_ids.entry(platform).or_default();
+                if data.id == *last_id {
+                    continue;
+                }
+                if let Err(err) = history::record_arbitration(platform, &data) {
+                    tracing::error!("record arbitration error: {}", err);
+                }
+
+                // 启动时已经开始的仲裁不提醒
+                if !last_id.is_empty() && ArbitrationLevel::from_data(&data) == ArbitrationLevel::T0
+                {
+                    let messages = [
+                        "好图!".parse_message_chain(),
+                        crate::wf_api::gen_arbitration_info(&data),
+                    ];
+                    for target in targets {
+                        for message in &messages {
+                            if let Err(err) =
+                                client.send_group_message(target, message.clone()).await
+                            {
+                                tracing::error!("arbitration notice to {} error: {}", target, err);
+                                break;
                             }
+                        }
+                    }
+                }
+                *last_id = data.id;
+            }
+        }
+    });
+}
+
+pub fn fissure(client: Arc<Client>) {
+    tokio::spawn(async move {
+        let mut announced: HashMap<Platform, HashSet<CompactStr>> = HashMap::new();
+
+        let mut timer = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            timer.tick().await;
+
+            let subscribers = subscribers_by_platform(Topic::Fissure);
+            // 没有群订阅的平台不再跟踪, 以后有群订阅时重新记录
+            announced.retain(|platform, _| subscribers.contains_key(platform));
+
+            for (platform, targets) in subscribers {
+                let data = match crate::wf_api::WF_API.fissures(platform).await {
+                    Ok(data) => data,
+                    Err(err) => {
+                        tracing::error!("{:?} fissure timing error: {}", platform, err);
+                        continue;
+                    }
+                };
+                let data = data.into_iter().filter(|f| !f.expired).collect::<Vec<_>>();
 
-                            last_id = data.id;
+                // 第一次只记录已有的裂缝, 不提醒
+                let announced = match announced.get_mut(&platform) {
+                    Some(announced) => announced,
+                    None => {
+                        announced.insert(platform, data.into_iter().map(|f| f.id).collect());
+                        continue;
+                    }
+                };
+
+                let new = data
+                    .iter()
+                    .filter(|f| !announced.contains(&f.id))
+                    .collect::<Vec<_>>();
+                for (target, filters) in targets {
+                    let matched = new
+                        .iter()
+                        .filter(|f| {
+                            filters.is_empty() || filters.iter().any(|filter| f.matches(filter))
+                        })
+                        .map(|f| crate::wf_api::gen_fissure_line(f))
+                        .collect::<Vec<_>>();
+                    if matched.is_empty() {
+                        continue;
+                    }
+
+                    if let Err(err) = client
+                        .send_group_message(
+                            target,
+                            format!("新裂缝!\n{}", matched.join("\n")).parse_message_chain(),
+                        )
+                        .await
+                    {
+                        tracing::error!("fissure notice to {} error: {}", target, err);
+                    }
+                }
+
+                *announced = data.into_iter().map(|f| f.id).collect();
+            }
+        }
+    });
+}
+
+/// 把消息依次发给`targets`中的群.
+async fn broadcast(
+    client: &Client,
+    topic: Topic,
+    targets: &[(i64, Vec<CompactStr>)],
+    messages: Vec<MessageChain>,
+) {
+    for (target, _) in targets {
+        for message in &messages {
+            if let Err(err) = client.send_group_message(*target, message.clone()).await {
+                tracing::error!("{:?} notice to {} error: {}", topic, target, err);
+                break;
+            }
+        }
+    }
+}
+
+/// 突击和执刑官刷新时通知订阅的群.
+pub fn sortie(client: Arc<Client>) {
+    tokio::spawn(async move {
+        let mut last_sorties: HashMap<Platform, CompactStr> = HashMap::new();
+        let mut last_archons: HashMap<Platform, CompactStr> = HashMap::new();
+
+        let mut timer = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            timer.tick().await;
+
+            let subscribers = subscribers_by_platform(Topic::Sortie);
+            last_sorties.retain(|platform, _| subscribers.contains_key(platform));
+            for (platform, targets) in subscribers {
+                match crate::wf_api::WF_API.sortie(platform).await {
+                    Ok(data) => {
+                        let last_sortie = last_sorties.entry(platform).or_default();
+                        if data.id != *last_sortie {
+                            if !last_sortie.is_empty() {
+                                let messages = vec![
+                                    "突击刷新了!".parse_message_chain(),
+                                    crate::wf_api::gen_sortie_info(&data),
+                                ];
+                                broadcast(&client, Topic::Sortie, &targets, messages).await;
+                            }
+                            *last_sortie = data.id;
                         }
                     }
+                    Err(err) => tracing::error!("{:?} sortie timing error: {}", platform, err),
                 }
-                Err(err) => {
-                    tracing::error!("eidolon timing error: {}", err);
+            }
+
+            let subscribers = subscribers_by_platform(Topic::ArchonHunt);
+            last_archons.retain(|platform, _| subscribers.contains_key(platform));
+            for (platform, targets) in subscribers {
+                match crate::wf_api::WF_API.archon_hunt(platform).await {
+                    Ok(data) => {
+                        let last_archon = last_archons.entry(platform).or_default();
+                        if data.id != *last_archon {
+                            if !last_archon.is_empty() {
+                                let messages = vec![
+                                    "执刑官刷新了!".parse_message_chain(),
+                                    crate::wf_api::gen_archon_hunt_info(&data),
+                                ];
+                                broadcast(&client, Topic::ArchonHunt, &targets, messages).await;
+                            }
+                            *last_archon = data.id;
+                        }
+                    }
+                    Err(err) => {
+                        tracing::error!("{:?} archon hunt timing error: {}", platform, err)
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// 奸商到达时通知订阅的群, 商品按wm价格从高到低排列.
+pub fn void_trader(client: Arc<Client>) {
+    tokio::spawn(async move {
+        // 没有记录的平台代表还没查过
+        let mut last_ids: HashMap<Platform, CompactStr> = HashMap::new();
+
+        let mut timer = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            timer.tick().await;
+
+            let subscribers = subscribers_by_platform(Topic::VoidTrader);
+            last_ids.retain(|platform, _| subscribers.contains_key(platform));
+
+            for (platform, targets) in subscribers {
+                let data = match crate::wf_api::WF_API.void_trader(platform).await {
+                    Ok(data) => data,
+                    Err(err) => {
+                        tracing::error!("{:?} void trader timing error: {}", platform, err);
+                        continue;
+                    }
+                };
+                if !data.active {
+                    last_ids.entry(platform).or_default();
                     continue;
                 }
+
+                match last_ids.get(&platform) {
+                    Some(id) if *id == data.id => continue,
+                    // 启动时奸商已经在了, 不提醒
+                    None => {
+                        last_ids.insert(platform, data.id);
+                        continue;
+                    }
+                    _ => {}
+                }
+
+                let prices = crate::wf_api::WF_API
+                    .void_trader_prices(platform, &data.inventory, crate::db::exact_url_name)
+                    .await;
+                let messages = vec![
+                    "奸商来了!".parse_message_chain(),
+                    crate::wf_api::gen_void_trader_info(&data, Some(&prices)),
+                ];
+                broadcast(&client, Topic::VoidTrader, &targets, messages).await;
+
+                last_ids.insert(platform, data.id);
             }
         }
     });
 }
 
-pub fn arbitration(client: Arc<Client>) {
+/// 新一轮赏金的奖励池中有订阅的奖励时通知.
+pub fn bounty(client: Arc<Client>) {
     tokio::spawn(async move {
-        let mut last_id = CompactStr::new("");
+        let mut seen: HashMap<Platform, HashSet<CompactStr>> = HashMap::new();
 
-        let mut timer = tokio::time::interval(Duration::from_secs(30));
+        let mut timer = tokio::time::interval(Duration::from_secs(60));
         loop {
             timer.tick().await;
 
-            let data = match crate::wf_api::arbitration().await {
-                Ok(a) => a,
+            let subscribers = subscribers_by_platform(Topic::Bounty);
+            seen.retain(|platform, _| subscribers.contains_key(platform));
+
+            for (platform, targets) in subscribers {
+                let data = match crate::wf_api::WF_API.syndicate_missions(platform).await {
+                    Ok(data) => data,
+                    Err(err) => {
+                        tracing::error!("{:?} bounty timing error: {}", platform, err);
+                        continue;
+                    }
+                };
+
+                // 第一次只记录已有的赏金, 不提醒
+                let seen = match seen.get_mut(&platform) {
+                    Some(seen) => seen,
+                    None => {
+                        seen.insert(platform, data.into_iter().map(|m| m.id).collect());
+                        continue;
+                    }
+                };
+
+                let new = data
+                    .iter()
+                    .filter(|m| !m.jobs.is_empty() && !seen.contains(&m.id))
+                    .filter_map(|m| BountySyndicate::from_key(&m.syndicate_key).map(|s| (s, m)))
+                    .collect::<Vec<_>>();
+                for (target, filters) in targets {
+                    let mut matched = Vec::new();
+                    for (syndicate, mission) in &new {
+                        for job in &mission.jobs {
+                            let rewards = filters
+                                .iter()
+                                .flat_map(|filter| job.rewards_matching(filter))
+                                .unique()
+                                .join(", ");
+                            if !rewards.is_empty() {
+                                matched.push(format!(
+                                    "{} {}: {}",
+                                    syndicate.chinese(),
+                                    job.level_range(),
+                                    rewards
+                                ));
+                            }
+                        }
+                    }
+                    if matched.is_empty() {
+                        continue;
+                    }
+
+                    if let Err(err) = client
+                        .send_group_message(
+                            target,
+                            format!("赏金有想要的奖励!\n{}", matched.join("\n"))
+                                .parse_message_chain(),
+                        )
+                        .await
+                    {
+                        tracing::error!("bounty notice to {} error: {}", target, err);
+                    }
+                }
+
+                *seen = data.into_iter().map(|m| m.id).collect();
+            }
+        }
+    });
+}
+
+/// 新入侵的奖励在关注列表中时通知, 订阅时没有指定奖励的群使用默认关注列表.
+pub fn invasion(client: Arc<Client>) {
+    tokio::spawn(async move {
+        let mut seen: HashMap<Platform, HashSet<CompactStr>> = HashMap::new();
+
+        let mut timer = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            timer.tick().await;
+
+            let subscribers = subscribers_by_platform(Topic::Invasion);
+            seen.retain(|platform, _| subscribers.contains_key(platform));
+
+            for (platform, targets) in subscribers {
+                let data = match crate::wf_api::WF_API.invasions(platform).await {
+                    Ok(data) => data,
+                    Err(err) => {
+                        tracing::error!("{:?} invasion timing error: {}", platform, err);
+                        continue;
+                    }
+                };
+
+                // 第一次只记录已有的入侵, 不提醒
+                let seen = match seen.get_mut(&platform) {
+                    Some(seen) => seen,
+                    None => {
+                        seen.insert(platform, data.into_iter().map(|i| i.id).collect());
+                        continue;
+                    }
+                };
+
+                let new = data
+                    .iter()
+                    .filter(|i| !i.completed && !seen.contains(&i.id))
+                    .collect::<Vec<_>>();
+                for (target, filters) in targets {
+                    let matched = new
+                        .iter()
+                        .filter(|i| {
+                            if filters.is_empty() {
+                                DEFAULT_INVASION_WATCH.iter().any(|w| i.offers(w))
+                            } else {
+                                filters.iter().any(|w| i.offers(w))
+                            }
+                        })
+                        .map(|i| crate::wf_api::gen_invasion_line(i))
+                        .collect::<Vec<_>>();
+                    if matched.is_empty() {
+                        continue;
+                    }
+
+                    if let Err(err) = client
+                        .send_group_message(
+                            target,
+                            format!("入侵有好东西!\n{}", matched.join("\n")).parse_message_chain(),
+                        )
+                        .await
+                    {
+                        tracing::error!("invasion notice to {} error: {}", target, err);
+                    }
+                }
+
+                seen.extend(new.into_iter().map(|i| i.id.clone()));
+                seen.retain(|id| data.iter().any(|i| i.id == *id));
+            }
+        }
+    });
+}
+
+/// 周一重置时把执刑官, 钢铁之路奖励和午夜电波每周任务汇总发给订阅的群.
+/// 执刑官每周一刷新, 以它的id变化作为重置的标志.
+pub fn weekly_reset(client: Arc<Client>) {
+    tokio::spawn(async move {
+        let mut last_ids: HashMap<Platform, CompactStr> = HashMap::new();
+
+        let mut timer = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            timer.tick().await;
+
+            let subscribers = subscribers_by_platform(Topic::WeeklyReset);
+            last_ids.retain(|platform, _| subscribers.contains_key(platform));
+
+            for (platform, targets) in subscribers {
+                let api = &crate::wf_api::WF_API;
+                let archon = match api.archon_hunt(platform).await {
+                    Ok(data) => data,
+                    Err(err) => {
+                        tracing::error!("{:?} weekly reset timing error: {}", platform, err);
+                        continue;
+                    }
+                };
+                let last_id = last_ids.entry(platform).or_default();
+                if archon.id == *last_id {
+                    continue;
+                } else if last_id.is_empty() {
+                    *last_id = archon.id;
+                    continue;
+                }
+
+                let nightwave = api
+                    .nightwave(platform)
+                    .await
+                    .map_err(|err| tracing::warn!("weekly reset nightwave error: {}", err))
+                    .ok();
+                let steel_path = api
+                    .steel_path(platform)
+                    .await
+                    .map_err(|err| tracing::warn!("weekly reset steel path error: {}", err))
+                    .ok();
+                let digest = crate::wf_api::gen_weekly_digest(
+                    nightwave.as_ref(),
+                    Some(&archon),
+                    steel_path.as_ref(),
+                );
+                broadcast(&client, Topic::WeeklyReset, &targets, vec![digest]).await;
+
+                *last_id = archon.id;
+            }
+        }
+    });
+}
+
+/// 定时更新物品和紫卡数据库, 间隔为`.env`中的`catalog_refresh_hours`(默认24小时).
+/// 有物品增减时私聊告诉`owner`, 之后再更新物品的详细信息.
+pub fn catalog(client: Arc<Client>) {
+    tokio::spawn(async move {
+        let hours = dotenv::var("catalog_refresh_hours")
+            .ok()
+            .and_then(|hours| hours.parse::<u64>().ok())
+            .unwrap_or(24)
+            .max(1);
+        let mut timer = tokio::time::interval(Duration::from_secs(hours * 60 * 60));
+        loop {
+            timer.tick().await;
+
+            let api = &crate::wf_api::WF_API;
+            let mut reports = Vec::new();
+            match api.update_items_db(crate::db::ITEMS_DB.clone()).await {
+                Ok(update) => reports.extend(
+                    update
+                        .version
+                        .map(|version| crate::wf_api::gen_catalog_report("物品", &version)),
+                ),
+                Err(err) => tracing::error!("items catalog timing error: {}", err),
+            }
+            match api.update_rivens_db(crate::db::RIVENS_DB.clone()).await {
+                Ok(update) => reports.extend(
+                    update
+                        .version
+                        .map(|version| crate::wf_api::gen_catalog_report("紫卡武器", &version)),
+                ),
+                Err(err) => tracing::error!("rivens catalog timing error: {}", err),
+            }
+            if !reports.is_empty() {
+                report_catalog(&client, &reports.join("\n\n")).await;
+            }
+
+            // 详细信息要逐个请求, 在报告之后更新
+            if let Err(err) = api.update_item_meta(crate::db::ITEMS_DB.clone()).await {
+                tracing::error!("item meta timing error: {}", err);
+            }
+        }
+    });
+}
+
+/// 私聊`owner`目录的更新报告
+async fn report_catalog(client: &Client, report: &str) {
+    let owner = match dotenv::var("owner").map(|owner| owner.parse::<i64>()) {
+        Ok(Ok(owner)) => owner,
+        _ => {
+            tracing::warn!("no valid owner to report catalog update");
+            return;
+        }
+    };
+    if let Err(err) = client
+        .send_friend_message(owner, report.parse_message_chain())
+        .await
+    {
+        tracing::error!("report catalog update to {} error: {}", owner, err);
+    }
+}
+
+/// 定时计算各群所用平台的杜卡特效率排行并缓存, 间隔为`.env`中的`ducat_refresh_hours`(默认6小时).
+/// 物品信息还不全(第一次`update_item_meta`还没完成)时10分钟后重试.
+pub fn ducats() {
+    tokio::spawn(async move {
+        let hours = dotenv::var("ducat_refresh_hours")
+            .ok()
+            .and_then(|hours| hours.parse::<u64>().ok())
+            .unwrap_or(6)
+            .max(1);
+        loop {
+            let platforms = settings::platforms().unwrap_or_else(|err| {
+                tracing::error!("ducat ranking platforms error: {}", err);
+                vec![Platform::Pc]
+            });
+            let mut ready = true;
+            for platform in platforms {
+                match crate::wf_api::WF_API
+                    .update_ducat_ranking(platform, crate::db::ITEMS_DB.clone())
+                    .await
+                {
+                    Ok(Some(_)) => {}
+                    Ok(None) => ready = false,
+                    Err(err) => tracing::error!("{:?} ducat ranking error: {}", platform, err),
+                }
+            }
+
+            let wait = if ready { hours * 60 * 60 } else { 10 * 60 };
+            tokio::time::sleep(Duration::from_secs(wait)).await;
+        }
+    });
+}
+
+/// 检查所有盯价, 间隔为`.env`中的`price_watch_minutes`(默认5分钟).
+/// 同一平台同一物品的盯价只请求一次订单, 请求受`wm_rate_limit`限制.
+/// 有游戏中的卖家不高于盯的价格时, 在群里盯的@提醒, 私聊盯的私聊提醒, 同一盯价只在价格更低时再提醒.
+pub fn price_watch(client: Arc<Client>) {
+    tokio::spawn(async move {
+        let minutes = dotenv::var("price_watch_minutes")
+            .ok()
+            .and_then(|minutes| minutes.parse::<u64>().ok())
+            .unwrap_or(5)
+            .max(1);
+        let mut timer = tokio::time::interval(Duration::from_secs(minutes * 60));
+        loop {
+            timer.tick().await;
+
+            let all = match watches::all_watches() {
+                Ok(all) => all,
                 Err(err) => {
-                    tracing::error!("arbitration timing error: {}", err);
+                    tracing::error!("price watches error: {}", err);
                     continue;
                 }
             };
-            if data.id == last_id {
-                continue;
-            } else if last_id.is_empty() {
-                last_id = data.id;
-            } else if let ArbitrationLevel::T0 = ArbitrationLevel::from_data(&data) {
-                let targets = dotenv::var("arbitration_notice")
-                    .unwrap_or_default()
-                    .split(",")
-                    .map(|x| x.parse().unwrap())
-                    .collect::<Vec<i64>>();
-
-                for target in targets {
-                    client
-                        .send_group_message(target, "好图!".parse_message_chain())
-                        .await
-                        .unwrap();
-                    client
-                        .send_group_message(target, crate::wf_api::gen_arbitration_info(&data))
-                        .await
-                        .unwrap();
+            let mut batches: HashMap<(Platform, CompactStr), Vec<(i64, PriceWatch)>> =
+                HashMap::new();
+            for (user, user_watches) in all {
+                if user_watches.iter().any(PriceWatch::is_expired) {
+                    if let Err(err) = watches::remove_expired(user) {
+                        tracing::error!("remove expired price watches of {} error: {}", user, err);
+                    }
+                }
+                for watch in user_watches.into_iter().filter(|w| !w.is_expired()) {
+                    batches
+                        .entry((watch.platform(), watch.url_name.clone()))
+                        .or_default()
+                        .push((user, watch));
                 }
+            }
 
-                last_id = data.id
+            for ((platform, url_name), batch) in batches {
+                let orders = match crate::wf_api::WF_API.wm_item(platform, &url_name).await {
+                    Ok(orders) => orders,
+                    Err(err) => {
+                        tracing::warn!("{:?} price watch {} error: {}", platform, url_name, err);
+                        continue;
+                    }
+                };
+                for (user, watch) in batch {
+                    let order = match crate::wf_api::lowest_ingame_sell(&orders, watch.mod_rank) {
+                        Some(order)
+                            if order.platinum <= watch.max_price
+                                && watch
+                                    .notified_price
+                                    .map_or(true, |price| order.platinum < price) =>
+                        {
+                            order
+                        }
+                        _ => continue,
+                    };
+                    let mut item_name = watch.item_name.to_string();
+                    if let Some(rank) = watch.mod_rank {
+                        item_name.push_str(&format!(" ({} 级)", rank));
+                    }
+                    let text = format!(
+                        "盯价提醒: {} {} 有游戏中的卖家 {} 卖 ${}, 库存 {} 个 (盯价 ${})",
+                        platform.name(),
+                        item_name,
+                        order.user.ingame_name,
+                        order.platinum,
+                        order.quantity,
+                        watch.max_price
+                    );
+                    let result = match watch.group {
+                        Some(group) => {
+                            let mut chain = MessageChain::default();
+                            chain.push(At::new(user));
+                            chain.push(Text::new(format!(" {}", text)));
+                            client.send_group_message(group, chain).await.map(|_| ())
+                        }
+                        None => client
+                            .send_friend_message(user, text.parse_message_chain())
+                            .await
+                            .map(|_| ()),
+                    };
+                    match result {
+                        Ok(_) => {
+                            if let Err(err) = watches::mark_notified(user, &watch, order.platinum) {
+                                tracing::error!("mark price watch of {} error: {}", user, err);
+                            }
+                        }
+                        Err(err) => {
+                            tracing::error!("price watch notice to {} error: {}", user, err)
+                        }
+                    }
+                }
             }
         }
     });
@@ -0,0 +1,151 @@
+//! 用户的wm盯价, 储存在sled中, key为qq号, value为json格式的`Vec<PriceWatch>`.
+
+use compact_str::CompactStr;
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+use crate::db::WATCHES_DB;
+use crate::wf_api::Platform;
+
+/// 每人最多的盯价数
+pub const MAX_WATCHES: usize = 10;
+/// 盯价的有效期
+pub const WATCH_DURATION: Duration = Duration::days(7);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PriceWatch {
+    pub url_name: CompactStr,
+    pub item_name: CompactStr,
+    /// 游戏中卖家的价格不高于这个时提醒
+    pub max_price: i32,
+    pub mod_rank: Option<i32>,
+    /// `Platform::worldstate`
+    platform: CompactStr,
+    /// 在群里盯价时在群里@提醒, 否则私聊
+    pub group: Option<i64>,
+    #[serde(with = "time::serde::timestamp")]
+    pub expiry: OffsetDateTime,
+    /// 上次提醒的价格, 之后只在价格更低时再提醒
+    #[serde(default)]
+    pub notified_price: Option<i32>,
+}
+
+impl PriceWatch {
+    pub fn new(
+        url_name: CompactStr,
+        item_name: CompactStr,
+        max_price: i32,
+        mod_rank: Option<i32>,
+        platform: Platform,
+        group: Option<i64>,
+    ) -> Self {
+        PriceWatch {
+            url_name,
+            item_name,
+            max_price,
+            mod_rank,
+            platform: CompactStr::new(platform.worldstate()),
+            group,
+            expiry: OffsetDateTime::now_utc() + WATCH_DURATION,
+            notified_price: None,
+        }
+    }
+
+    pub fn platform(&self) -> Platform {
+        Platform::from_alias(&self.platform).unwrap_or(Platform::Pc)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expiry <= OffsetDateTime::now_utc()
+    }
+
+    /// 同一物品, 等级和平台的盯价只保留一个
+    fn same_target(&self, other: &PriceWatch) -> bool {
+        self.url_name == other.url_name
+            && self.mod_rank == other.mod_rank
+            && self.platform == other.platform
+    }
+}
+
+/// 用户的盯价, 包括已过期还没清理的.
+pub fn watches(user: i64) -> anyhow::Result<Vec<PriceWatch>> {
+    match WATCHES_DB.get(user.to_be_bytes())? {
+        Some(value) => Ok(serde_json::from_slice(value.as_ref())?),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 所有用户的盯价
+pub fn all_watches() -> anyhow::Result<Vec<(i64, Vec<PriceWatch>)>> {
+    WATCHES_DB
+        .iter()
+        .map(|kv| {
+            let (user, watches) = kv?;
+            let user = i64::from_be_bytes(user.as_ref().try_into().unwrap_or_default());
+            Ok((user, serde_json::from_slice(watches.as_ref())?))
+        })
+        .collect()
+}
+
+/// 用sled的比较并交换修改用户的盯价, 与`timing::price_watch`同时修改时不会互相覆盖. 改完为空时删除.
+/// 有并发修改时`f`会对新的值再调用一次, 返回最后一次调用的结果.
+pub fn update_watches<T>(
+    user: i64,
+    mut f: impl FnMut(&mut Vec<PriceWatch>) -> T,
+) -> anyhow::Result<T> {
+    let mut result = None;
+    let mut error = None;
+    WATCHES_DB.fetch_and_update(user.to_be_bytes(), |old| {
+        error = None;
+        let mut watches = match old.map(serde_json::from_slice::<Vec<PriceWatch>>) {
+            Some(Ok(watches)) => watches,
+            None => Vec::new(),
+            Some(Err(err)) => {
+                error = Some(err);
+                return old.map(<[u8]>::to_vec);
+            }
+        };
+        result = Some(f(&mut watches));
+        if watches.is_empty() {
+            return None;
+        }
+        match serde_json::to_vec(&watches) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                error = Some(err);
+                old.map(<[u8]>::to_vec)
+            }
+        }
+    })?;
+    if let Some(err) = error {
+        return Err(err.into());
+    }
+    WATCHES_DB.flush()?;
+    Ok(result.expect("fetch_and_update calls f at least once"))
+}
+
+/// 添加盯价, 同一目标的旧盯价会被替换. 超过`MAX_WATCHES`时返回`false`.
+pub fn add_watch(user: i64, watch: PriceWatch) -> anyhow::Result<bool> {
+    update_watches(user, |watches| {
+        watches.retain(|w| !w.is_expired() && !w.same_target(&watch));
+        if watches.len() >= MAX_WATCHES {
+            return false;
+        }
+        watches.push(watch.clone());
+        true
+    })
+}
+
+/// 删除过期的盯价
+pub fn remove_expired(user: i64) -> anyhow::Result<()> {
+    update_watches(user, |watches| watches.retain(|w| !w.is_expired()))
+}
+
+/// 记录提醒过的价格, 盯价已被取消时什么都不做.
+pub fn mark_notified(user: i64, watch: &PriceWatch, price: i32) -> anyhow::Result<()> {
+    update_watches(user, |watches| {
+        if let Some(w) = watches.iter_mut().find(|w| w.same_target(watch)) {
+            w.notified_price = Some(price);
+        }
+    })
+}
@@ -0,0 +1,314 @@
+use std::sync::{Mutex, MutexGuard};
+
+use compact_str::CompactStr;
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use proc_qq::re_exports::ricq::msg::MessageChain;
+use proc_qq::MessageChainParseTrait;
+use serde::{Deserialize, Serialize};
+use time::macros::{format_description, offset};
+use time::{Duration, OffsetDateTime};
+
+use crate::wf_api::json_file::JsonFile;
+use crate::wf_api::{cache, Enemy, Platform, WarframeApi, WfApiResult};
+
+/// 没有评级表文件时使用的默认评级
+const DEFAULT_TIERS: &str = include_str!("arbitration_tiers.json");
+
+/// 评级表, 路径为`.env`中的`arbitration_tiers`, 默认`arbitration_tiers.json`.
+static TIER_TABLE: Lazy<Mutex<JsonFile<Vec<TierRule>>>> = Lazy::new(|| {
+    let path =
+        dotenv::var("arbitration_tiers").unwrap_or_else(|_| "arbitration_tiers.json".to_owned());
+    let default = serde_json::from_str(DEFAULT_TIERS).expect("default arbitration tiers err");
+    Mutex::new(JsonFile::new(path, default))
+});
+
+/// 仲裁轮换表, 路径为`.env`中的`arbitration_schedule`, 默认`arbitration_schedule.json`.
+static SCHEDULE: Lazy<Mutex<JsonFile<Vec<ScheduledArbitration>>>> = Lazy::new(|| {
+    let path = dotenv::var("arbitration_schedule")
+        .unwrap_or_else(|_| "arbitration_schedule.json".to_owned());
+    Mutex::new(JsonFile::new(path, Vec::new()))
+});
+
+/// 每个仲裁持续一小时
+pub const ARBITRATION_DURATION: Duration = Duration::HOUR;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ArbitrationLevel {
+    T0,
+    T1,
+    Bad,
+}
+
+impl ArbitrationLevel {
+    pub fn nickname(&self) -> &'static str {
+        match self {
+            ArbitrationLevel::T0 => "打它丫的",
+            ArbitrationLevel::T1 => "可以打但没必要",
+            ArbitrationLevel::Bad => "垃圾图/未定级",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "t0" => Some(ArbitrationLevel::T0),
+            "t1" => Some(ArbitrationLevel::T1),
+            "bad" | "垃圾" => Some(ArbitrationLevel::Bad),
+            _ => None,
+        }
+    }
+
+    /// 按评级表评级, 表中没有的为`Bad`.
+    pub fn from_data(data: &Arbitration) -> Self {
+        arbitration_tier(
+            data.node_key.as_deref().unwrap_or(&data.node),
+            &data.type_key,
+            &data.enemy,
+        )
+    }
+}
+
+/// 评级表中的一条规则
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TierRule {
+    /// 节点的`nodeKey`, 如`Hydron (Sedna)`. 只写星球名如`Sedna`时匹配整个星球.
+    pub node: CompactStr,
+    /// 任务的`typeKey`, 如`Defense`
+    #[serde(rename = "type")]
+    pub r#type: CompactStr,
+    /// 为空时匹配所有敌人
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enemy: Option<Enemy>,
+    pub tier: ArbitrationLevel,
+}
+
+impl TierRule {
+    fn same_target(&self, node: &str, r#type: &str, enemy: &Option<Enemy>) -> bool {
+        self.node.eq_ignore_ascii_case(node)
+            && self.r#type.eq_ignore_ascii_case(r#type)
+            && self.enemy == *enemy
+    }
+
+    /// 匹配时返回具体程度, 节点比星球具体, 指定敌人比不指定具体.
+    fn specificity(&self, node_key: &str, type_key: &str, enemy: &Enemy) -> Option<u8> {
+        if !self.r#type.eq_ignore_ascii_case(type_key)
+            || matches!(&self.enemy, Some(e) if e != enemy)
+        {
+            return None;
+        }
+
+        let planet = planet_of(node_key).unwrap_or_default();
+        let node = if self.node.eq_ignore_ascii_case(node_key) {
+            2
+        } else if self.node.eq_ignore_ascii_case(planet) {
+            0
+        } else {
+            return None;
+        };
+        Some(node + self.enemy.is_some() as u8)
+    }
+}
+
+/// `Hydron (Sedna)`中的`Sedna`
+fn planet_of(node_key: &str) -> Option<&str> {
+    node_key
+        .rsplit_once('(')
+        .map(|(_, planet)| planet.trim_end_matches(')').trim())
+}
+
+/// 取得评级表, 文件修改过会先重新读取.
+fn tier_table() -> MutexGuard<'static, JsonFile<Vec<TierRule>>> {
+    let mut table = TIER_TABLE.lock().unwrap_or_else(|err| err.into_inner());
+    table.refresh();
+    table
+}
+
+/// 在评级表中找最具体的匹配规则, 没有匹配的为`Bad`.
+pub fn arbitration_tier(node_key: &str, type_key: &str, enemy: &Enemy) -> ArbitrationLevel {
+    tier_table()
+        .value
+        .iter()
+        .filter_map(|rule| {
+            rule.specificity(node_key, type_key, enemy)
+                .map(|specificity| (specificity, rule.tier))
+        })
+        .max_by_key(|(specificity, _)| *specificity)
+        .map(|(_, tier)| tier)
+        .unwrap_or(ArbitrationLevel::Bad)
+}
+
+/// 节点中包含`keyword`(不区分大小写)的规则
+pub fn tier_rules(keyword: &str) -> Vec<TierRule> {
+    let keyword = keyword.to_ascii_lowercase();
+    tier_table()
+        .value
+        .iter()
+        .filter(|rule| rule.node.to_ascii_lowercase().contains(&keyword))
+        .cloned()
+        .collect()
+}
+
+/// 添加规则并写回文件, 已有相同节点, 任务类型和敌人的规则时替换它.
+pub fn set_tier_rule(rule: TierRule) -> anyhow::Result<()> {
+    let mut table = tier_table();
+    match table
+        .value
+        .iter_mut()
+        .find(|r| r.same_target(&rule.node, &rule.r#type, &rule.enemy))
+    {
+        Some(old) => *old = rule,
+        None => table.value.push(rule),
+    }
+    table.save()
+}
+
+/// 删除规则并写回文件, 返回规则是否存在.
+pub fn remove_tier_rule(node: &str, r#type: &str, enemy: Option<Enemy>) -> anyhow::Result<bool> {
+    let mut table = tier_table();
+    let len = table.value.len();
+    table
+        .value
+        .retain(|rule| !rule.same_target(node, r#type, &enemy));
+    if table.value.len() == len {
+        return Ok(false);
+    }
+    table.save()?;
+    Ok(true)
+}
+
+/// 轮换表中的一个仲裁, 节点和任务类型与评级表一样用`nodeKey`和`typeKey`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScheduledArbitration {
+    #[serde(with = "time::serde::timestamp")]
+    pub start: OffsetDateTime,
+    pub node: CompactStr,
+    #[serde(rename = "type")]
+    pub r#type: CompactStr,
+    pub enemy: Enemy,
+}
+
+impl ScheduledArbitration {
+    pub fn level(&self) -> ArbitrationLevel {
+        arbitration_tier(&self.node, &self.r#type, &self.enemy)
+    }
+}
+
+fn schedule() -> MutexGuard<'static, JsonFile<Vec<ScheduledArbitration>>> {
+    let mut schedule = SCHEDULE.lock().unwrap_or_else(|err| err.into_inner());
+    schedule.refresh();
+    schedule
+}
+
+/// 轮换表中`from`之后`hours`小时内的仲裁, 包括`from`时正在进行的.
+pub fn arbitration_forecast(from: OffsetDateTime, hours: i64) -> Vec<ScheduledArbitration> {
+    let end = from + Duration::hours(hours);
+    schedule()
+        .value
+        .iter()
+        .filter(|a| a.start + ARBITRATION_DURATION > from && a.start < end)
+        .sorted_by_key(|a| a.start)
+        .cloned()
+        .collect()
+}
+
+/// 轮换表中`from`之后开始的第一个T0仲裁
+pub fn next_good_arbitration(from: OffsetDateTime) -> Option<ScheduledArbitration> {
+    schedule()
+        .value
+        .iter()
+        .filter(|a| a.start > from && a.level() == ArbitrationLevel::T0)
+        .min_by_key(|a| a.start)
+        .cloned()
+}
+
+/// 轮换表中`at`时的仲裁
+pub fn scheduled_arbitration(at: OffsetDateTime) -> Option<ScheduledArbitration> {
+    schedule()
+        .value
+        .iter()
+        .find(|a| a.start <= at && at < a.start + ARBITRATION_DURATION)
+        .cloned()
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Arbitration {
+    pub id: CompactStr,
+    #[serde(with = "time::serde::iso8601")]
+    pub activation: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub expiry: OffsetDateTime,
+
+    #[serde(rename = "startString")]
+    pub start_string: Option<CompactStr>,
+
+    pub active: Option<bool>,
+    pub node: CompactStr,
+    #[serde(rename = "nodeKey")]
+    pub node_key: Option<CompactStr>,
+
+    pub enemy: Enemy,
+    #[serde(rename = "enemyKey")]
+    pub enemy_key: Option<Enemy>,
+
+    #[serde(rename = "type")]
+    pub r#type: CompactStr,
+    #[serde(rename = "typeKey")]
+    pub type_key: CompactStr,
+
+    pub archwing: bool,
+    pub sharkwing: bool,
+}
+
+impl WarframeApi {
+    pub async fn arbitration(&self, platform: Platform) -> WfApiResult<Arbitration> {
+        let path = "/arbitration?language=zh";
+        self.cached_json(
+            &format!("api:{}{}", platform.worldstate(), path),
+            |a: &Arbitration| cache::until_expiry(a.expiry),
+            self.api_get(platform, path),
+        )
+        .await
+    }
+}
+
+pub fn gen_arbitration_info(data: &Arbitration) -> MessageChain {
+    // minutes
+    let remaining = (data.expiry - OffsetDateTime::now_utc()).whole_minutes();
+    format!(
+        "节点: {node} \n剩余时间(约): {time} 分钟 \n类型: {ty} \n敌人: {enemy} \n个人评价: {level}",
+        node = &data.node,
+        time = remaining,
+        ty = data.r#type,
+        enemy = data.enemy.nickname(),
+        level = ArbitrationLevel::from_data(data).nickname(),
+    )
+    .parse_message_chain()
+}
+
+/// 北京时间的`月-日 时:分`
+pub fn format_time(time: OffsetDateTime) -> String {
+    time.to_offset(offset!(+8))
+        .format(format_description!("[month]-[day] [hour]:[minute]"))
+        .unwrap_or_default()
+}
+
+pub fn gen_scheduled_line(data: &ScheduledArbitration) -> String {
+    format!(
+        "{time} {node} {ty} {enemy} [{level:?}]",
+        time = format_time(data.start),
+        node = data.node,
+        ty = data.r#type,
+        enemy = data.enemy.nickname(),
+        level = data.level(),
+    )
+}
+
+pub fn gen_forecast_info(data: &[ScheduledArbitration]) -> MessageChain {
+    if data.is_empty() {
+        return "轮换表里没有接下来的仲裁, 可能需要更新轮换表".parse_message_chain();
+    }
+    data.iter()
+        .map(gen_scheduled_line)
+        .join("\n")
+        .parse_message_chain()
+}
@@ -0,0 +1,179 @@
+use compact_str::CompactStr;
+use itertools::Itertools;
+use proc_qq::re_exports::ricq::msg::MessageChain;
+use proc_qq::MessageChainParseTrait;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::wf_api::{cache, remaining_time, Platform, WarframeApi, WfApiResult};
+
+/// 有赏金任务的集团
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BountySyndicate {
+    Ostron,
+    SolarisUnited,
+    Entrati,
+}
+
+impl BountySyndicate {
+    pub const ALL: &'static [BountySyndicate] = &[
+        BountySyndicate::Ostron,
+        BountySyndicate::SolarisUnited,
+        BountySyndicate::Entrati,
+    ];
+
+    /// worldstate中的`syndicateKey`
+    fn key(&self) -> &'static str {
+        match self {
+            BountySyndicate::Ostron => "Ostrons",
+            BountySyndicate::SolarisUnited => "Solaris United",
+            BountySyndicate::Entrati => "Entrati",
+        }
+    }
+
+    pub fn chinese(&self) -> &'static str {
+        match self {
+            BountySyndicate::Ostron => "希图斯",
+            BountySyndicate::SolarisUnited => "索拉里斯联盟",
+            BountySyndicate::Entrati => "英择谛",
+        }
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            BountySyndicate::Ostron => &["希图斯", "平原", "夜灵平原", "ostron", "ostrons"],
+            BountySyndicate::SolarisUnited => &[
+                "索拉里斯联盟",
+                "索拉里斯",
+                "福尔图娜",
+                "山谷",
+                "solaris",
+                "solarisunited",
+            ],
+            BountySyndicate::Entrati => &[
+                "英择谛",
+                "殁世幽都",
+                "魔胎之境",
+                "魔胎",
+                "火卫二",
+                "entrati",
+            ],
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|s| s.key() == key)
+    }
+
+    pub fn from_alias(name: &str) -> Option<Self> {
+        let name = name.to_lowercase().replace(' ', "");
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|s| s.aliases().contains(&name.as_str()))
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Bounty {
+    #[serde(rename = "type")]
+    pub r#type: Option<CompactStr>,
+    #[serde(rename = "rewardPool", default)]
+    pub reward_pool: Vec<CompactStr>,
+    #[serde(rename = "enemyLevels", default)]
+    pub enemy_levels: Vec<i32>,
+    #[serde(rename = "standingStages", default)]
+    pub standing_stages: Vec<i32>,
+    #[serde(rename = "minMR")]
+    pub min_mr: Option<i32>,
+}
+
+impl Bounty {
+    pub fn level_range(&self) -> String {
+        match (self.enemy_levels.first(), self.enemy_levels.last()) {
+            (Some(min), Some(max)) => format!("Lv.{}-{}", min, max),
+            _ => "Lv.?".to_owned(),
+        }
+    }
+
+    /// 奖励池中包含`keyword`(不区分大小写和空格)的奖励
+    pub fn rewards_matching<'a>(&'a self, keyword: &'a str) -> impl Iterator<Item = &'a str> {
+        let keyword = keyword.to_lowercase().replace(' ', "");
+        self.reward_pool
+            .iter()
+            .filter(move |reward| reward.to_lowercase().replace(' ', "").contains(&keyword))
+            .map(|reward| reward.as_str())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SyndicateMission {
+    pub id: CompactStr,
+    #[serde(with = "time::serde::iso8601")]
+    pub activation: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub expiry: OffsetDateTime,
+
+    pub syndicate: CompactStr,
+    #[serde(rename = "syndicateKey")]
+    pub syndicate_key: CompactStr,
+    #[serde(default)]
+    pub jobs: Vec<Bounty>,
+}
+
+impl WarframeApi {
+    pub async fn syndicate_missions(
+        &self,
+        platform: Platform,
+    ) -> WfApiResult<Vec<SyndicateMission>> {
+        let path = "/syndicateMissions?language=zh";
+        self.cached_json(
+            &format!("api:{}{}", platform.worldstate(), path),
+            |missions: &Vec<SyndicateMission>| {
+                cache::until_earliest(
+                    missions
+                        .iter()
+                        .filter(|m| !m.jobs.is_empty())
+                        .map(|m| m.expiry),
+                )
+            },
+            self.api_get(platform, path),
+        )
+        .await
+    }
+
+    pub async fn bounties(
+        &self,
+        platform: Platform,
+        syndicate: BountySyndicate,
+    ) -> WfApiResult<Option<SyndicateMission>> {
+        Ok(self
+            .syndicate_missions(platform)
+            .await?
+            .into_iter()
+            .find(|m| m.syndicate_key == syndicate.key()))
+    }
+}
+
+pub fn gen_bounties_info(syndicate: BountySyndicate, data: &SyndicateMission) -> MessageChain {
+    let mut info = format!(
+        "{}赏金 剩余时间(约): {}",
+        syndicate.chinese(),
+        remaining_time(data.expiry)
+    );
+    data.jobs.iter().enumerate().for_each(|(i, job)| {
+        info.push_str(&format!("\n{}. {}", i + 1, job.level_range()));
+        if let Some(ty) = &job.r#type {
+            info.push_str(&format!(" {}", ty));
+        }
+        if !job.standing_stages.is_empty() {
+            info.push_str(&format!(
+                "\n   {}个阶段 声望 {}",
+                job.standing_stages.len(),
+                job.standing_stages.iter().join("/")
+            ));
+        }
+        info.push_str(&format!("\n   奖励: {}", job.reward_pool.iter().join(", ")));
+    });
+    info.parse_message_chain()
+}
@@ -0,0 +1,131 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use compact_str::CompactStr;
+use time::OffsetDateTime;
+
+use crate::wf_api::WfApiResult;
+
+/// worldstate数据过期后接口可能还没更新, 至少缓存这么久避免每次都去请求.
+const MIN_WORLDSTATE_TTL: Duration = Duration::from_secs(30);
+const MAX_WORLDSTATE_TTL: Duration = Duration::from_secs(60 * 60);
+/// key的数量达到这个值时清理一次过期的缓存, 之后的阈值为清理后数量的两倍(不低于这个值).
+const PURGE_THRESHOLD: usize = 512;
+
+type Value = Arc<dyn Any + Send + Sync>;
+type Slot = Arc<tokio::sync::Mutex<Option<Entry>>>;
+
+struct Entry {
+    fetched_at: Instant,
+    expires_at: Instant,
+    result: WfApiResult<Value>,
+}
+
+/// 以`endpoint+参数`为key的响应缓存.
+///
+/// 每个key有一把异步锁, 同时到达的相同请求只有第一个会真正发出,
+/// 其余的等它完成后直接共享结果(包括错误).
+#[derive(Clone, Default)]
+pub struct Cache {
+    slots: Arc<Mutex<Slots>>,
+}
+
+struct Slots {
+    map: HashMap<CompactStr, Slot>,
+    /// 达到这个数量时清理, 避免清理不掉时每次插入都扫描一遍
+    next_purge_len: usize,
+}
+
+impl Default for Slots {
+    fn default() -> Self {
+        Slots {
+            map: HashMap::new(),
+            next_purge_len: PURGE_THRESHOLD,
+        }
+    }
+}
+
+impl Cache {
+    pub async fn get_or_fetch<T, F, Fut>(
+        &self,
+        key: &str,
+        ttl: F,
+        fetch: Fut,
+    ) -> WfApiResult<Arc<T>>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce(&T) -> Duration,
+        Fut: Future<Output = WfApiResult<T>>,
+    {
+        let requested_at = Instant::now();
+        let slot = self.slot(key);
+        let mut entry = slot.lock().await;
+
+        if let Some(cached) = entry.as_ref() {
+            let fresh = cached.result.is_ok() && cached.expires_at > Instant::now();
+            if fresh || cached.fetched_at >= requested_at {
+                return cached.result.clone().map(|value| {
+                    value
+                        .downcast::<T>()
+                        .unwrap_or_else(|_| panic!("cache type mismatch: {}", key))
+                });
+            }
+        }
+
+        let result = fetch.await.map(Arc::new);
+        let now = Instant::now();
+        *entry = Some(Entry {
+            fetched_at: now,
+            expires_at: match &result {
+                Ok(value) => now + ttl(value),
+                Err(_) => now,
+            },
+            result: result.clone().map(|value| value as Value),
+        });
+
+        result
+    }
+
+    fn slot(&self, key: &str) -> Slot {
+        let mut slots = self.slots.lock().unwrap();
+        if slots.map.len() >= slots.next_purge_len {
+            let now = Instant::now();
+            slots.map.retain(|_, slot| {
+                Arc::strong_count(slot) > 1
+                    || match slot.try_lock() {
+                        Ok(entry) => matches!(entry.as_ref(), Some(e) if e.expires_at > now),
+                        Err(_) => true,
+                    }
+            });
+            slots.next_purge_len = (slots.map.len() * 2).max(PURGE_THRESHOLD);
+        }
+        slots.map.entry(CompactStr::new(key)).or_default().clone()
+    }
+}
+
+impl fmt::Debug for Cache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cache")
+            .field("keys", &self.slots.lock().unwrap().map.len())
+            .finish()
+    }
+}
+
+/// 一组worldstate对象缓存到其中最早的`expiry`为止.
+pub fn until_earliest(expiries: impl IntoIterator<Item = OffsetDateTime>) -> Duration {
+    expiries
+        .into_iter()
+        .min()
+        .map(until_expiry)
+        .unwrap_or(MIN_WORLDSTATE_TTL)
+}
+
+/// worldstate对象缓存到它的`expiry`为止.
+pub fn until_expiry(expiry: OffsetDateTime) -> Duration {
+    let remaining = (expiry - OffsetDateTime::now_utc()).whole_seconds().max(0) as u64;
+    Duration::from_secs(remaining).clamp(MIN_WORLDSTATE_TTL, MAX_WORLDSTATE_TTL)
+}
@@ -0,0 +1,165 @@
+use std::collections::{HashMap, HashSet};
+
+use compact_str::CompactStr;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::resolve::invalidate_indexes;
+
+/// 物品和紫卡数据库中的tree, key为大端序的版本号, value为json格式的`CatalogVersion`.
+pub const CATALOG_VERSIONS_TREE: &str = "catalog_versions";
+
+/// 更新报告中最多列出的名字数
+const MAX_LISTED_NAMES: usize = 10;
+
+/// 目录(名字到`url_name`的索引)的一个版本, 只在有物品增减时记录.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CatalogVersion {
+    pub version: u32,
+    #[serde(with = "time::serde::timestamp")]
+    pub updated: OffsetDateTime,
+    /// 这一版共有的物品数
+    pub total: u32,
+    /// 比上一版新增的物品名
+    pub added: Vec<CompactStr>,
+    /// 比上一版移除的物品名
+    pub removed: Vec<CompactStr>,
+}
+
+/// 用`(物品名, url_name)`替换`db`中的名字索引, 删除不再存在的名字.
+/// 有物品增减时记录并返回新版本, 第一次储存时只记录不返回.
+pub(crate) fn replace_catalog(
+    db: &sled::Db,
+    items: &[(CompactStr, CompactStr)],
+) -> anyhow::Result<Option<CatalogVersion>> {
+    let keys = items
+        .iter()
+        .map(|(item_name, _)| item_name.to_ascii_lowercase().replace(' ', ""))
+        .collect::<Vec<_>>();
+    let new_keys = keys.iter().map(String::as_str).collect::<HashSet<_>>();
+    let new_urls = items
+        .iter()
+        .map(|(_, url_name)| url_name.as_str())
+        .collect::<HashSet<_>>();
+
+    // url_name -> 旧的名字
+    let mut old_urls = HashMap::new();
+    for entry in db.iter() {
+        let (key, url_name) = entry?;
+        let key = String::from_utf8_lossy(key.as_ref()).into_owned();
+        if !new_keys.contains(key.as_str()) {
+            db.remove(key.as_bytes())?;
+        }
+        old_urls
+            .entry(String::from_utf8_lossy(url_name.as_ref()).into_owned())
+            .or_insert(key);
+    }
+
+    let mut added = Vec::new();
+    for ((item_name, url_name), key) in items.iter().zip(&keys) {
+        if !old_urls.contains_key(url_name.as_str()) {
+            added.push(item_name.clone());
+        }
+        db.insert(key.as_bytes(), url_name.as_bytes())?;
+    }
+    // 第一次储存时所有物品都是新增的, 只记录版本不报告
+    let first = old_urls.is_empty();
+    let removed = old_urls
+        .into_iter()
+        .filter(|(url_name, _)| !new_urls.contains(url_name.as_str()))
+        .map(|(_, name)| CompactStr::from(name))
+        .sorted()
+        .collect::<Vec<_>>();
+    invalidate_indexes();
+
+    if added.is_empty() && removed.is_empty() {
+        return Ok(None);
+    }
+
+    let versions = db.open_tree(CATALOG_VERSIONS_TREE)?;
+    let version = match versions.last()? {
+        Some((key, _)) => u32::from_be_bytes(key.as_ref().try_into()?) + 1,
+        None => 1,
+    };
+    let version = CatalogVersion {
+        version,
+        updated: OffsetDateTime::now_utc(),
+        total: items.len() as u32,
+        added: if first { Vec::new() } else { added },
+        removed,
+    };
+    versions.insert(version.version.to_be_bytes(), serde_json::to_vec(&version)?)?;
+
+    Ok((!first).then_some(version))
+}
+
+fn listed_names(names: &[CompactStr]) -> String {
+    let mut listed = names.iter().take(MAX_LISTED_NAMES).join(", ");
+    if names.len() > MAX_LISTED_NAMES {
+        listed.push_str(" 等");
+    }
+    listed
+}
+
+/// 目录更新报告, 如`物品目录已更新到第 3 版, 共 3500 个\n新增 12 个物品: ...`.
+pub fn gen_catalog_report(catalog: &str, version: &CatalogVersion) -> String {
+    let mut report = format!(
+        "{}目录已更新到第 {} 版, 共 {} 个",
+        catalog, version.version, version.total
+    );
+    if !version.added.is_empty() {
+        report.push_str(&format!(
+            "\n新增 {} 个{}: {}",
+            version.added.len(),
+            catalog,
+            listed_names(&version.added)
+        ));
+    }
+    if !version.removed.is_empty() {
+        report.push_str(&format!(
+            "\n移除 {} 个{}: {}",
+            version.removed.len(),
+            catalog,
+            listed_names(&version.removed)
+        ));
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(items: &[(&str, &str)]) -> Vec<(CompactStr, CompactStr)> {
+        items
+            .iter()
+            .map(|(name, url_name)| (CompactStr::new(name), CompactStr::new(url_name)))
+            .collect()
+    }
+
+    #[test]
+    fn replace_catalog_versions() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+
+        // 第一次储存只记录版本
+        let first = items(&[
+            ("赤毒", "virulent_scourge"),
+            ("Volt Prime 一套", "volt_prime_set"),
+        ]);
+        assert!(replace_catalog(&db, &first).unwrap().is_none());
+        assert_eq!(db.open_tree(CATALOG_VERSIONS_TREE).unwrap().len(), 1);
+        assert!(db.contains_key("voltprime一套").unwrap());
+
+        assert!(replace_catalog(&db, &first).unwrap().is_none());
+
+        let second = items(&[("赤毒", "virulent_scourge"), ("绝路", "rubico")]);
+        let version = replace_catalog(&db, &second).unwrap().unwrap();
+        assert_eq!(version.version, 2);
+        assert_eq!(version.total, 2);
+        assert_eq!(version.added, ["绝路"]);
+        assert_eq!(version.removed, ["voltprime一套"]);
+        assert!(!db.contains_key("voltprime一套").unwrap());
+        assert_eq!(db.get("绝路").unwrap().as_deref(), Some(&b"rubico"[..]));
+    }
+}
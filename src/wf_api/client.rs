@@ -0,0 +1,236 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use compact_str::CompactStr;
+use once_cell::sync::Lazy;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+
+use crate::wf_api::cache::Cache;
+use crate::wf_api::{Platform, WfApiError, WfApiResult};
+
+/// 不含平台, 请求时拼上`/pc`, `/ps4`等
+pub const DEFAULT_API_URL: &str = "https://api.warframestat.us";
+pub const DEFAULT_WM_API_URL: &str = "https://api.warframe.market/v1";
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+/// warframe.market要求每秒不超过3个请求
+pub const DEFAULT_WM_RATE_LIMIT: u32 = 3;
+
+/// 全局共享的api客户端, 配置从`.env`读取.
+pub static WF_API: Lazy<WarframeApi> =
+    Lazy::new(|| WarframeApi::from_env().expect("WF_API init err"));
+
+/// 持有一个带连接池的`reqwest::Client`, 所有`warframestat.us`和`warframe.market`的请求都通过它发出.
+#[derive(Debug, Clone)]
+pub struct WarframeApi {
+    client: reqwest::Client,
+    cache: Cache,
+    api_url: CompactStr,
+    wm_api_url: CompactStr,
+    wm_interval: Duration,
+    /// 下一个warframe.market请求最早可以发出的时间
+    wm_next: Arc<tokio::sync::Mutex<Instant>>,
+}
+
+impl WarframeApi {
+    pub fn builder() -> WarframeApiBuilder {
+        WarframeApiBuilder::default()
+    }
+
+    /// 读取`.env`中的`wf_api_url`, `wm_api_url`, `api_timeout`(秒), `wm_rate_limit`(每秒请求数),
+    /// `user_agent`和`api_headers`(`名:值`, 多个用,分割), 未配置的项使用默认值.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let mut builder = Self::builder();
+
+        if let Ok(url) = dotenv::var("wf_api_url") {
+            builder = builder.api_url(url);
+        }
+        if let Ok(url) = dotenv::var("wm_api_url") {
+            builder = builder.wm_api_url(url);
+        }
+        if let Ok(secs) = dotenv::var("api_timeout") {
+            builder = builder.timeout(Duration::from_secs(secs.parse()?));
+        }
+        if let Ok(limit) = dotenv::var("wm_rate_limit") {
+            builder = builder.wm_rate_limit(limit.parse()?);
+        }
+        if let Ok(ua) = dotenv::var("user_agent") {
+            builder = builder.user_agent(ua);
+        }
+        if let Ok(headers) = dotenv::var("api_headers") {
+            for header in headers.split(',').filter(|h| !h.trim().is_empty()) {
+                let (name, value) = header
+                    .split_once(':')
+                    .ok_or_else(|| anyhow::anyhow!("api_headers格式错误: {}", header))?;
+                builder = builder.header(name.trim(), value.trim())?;
+            }
+        }
+
+        builder.build()
+    }
+
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    pub fn wm_api_url(&self) -> &str {
+        &self.wm_api_url
+    }
+
+    /// `warframestat.us`上`platform`的GET请求, `path`以`/`开头.
+    pub(crate) fn api_get(&self, platform: Platform, path: &str) -> RequestBuilder {
+        self.client.get(format!(
+            "{}/{}{}",
+            self.api_url,
+            platform.worldstate(),
+            path
+        ))
+    }
+
+    /// `warframe.market`的GET请求, `path`以`/`开头.
+    /// 物品列表等各平台相同的数据用这个, 订单等按平台区分的用`wm_platform_get`.
+    pub(crate) fn wm_get(&self, path: &str) -> RequestBuilder {
+        self.client.get(format!("{}{}", self.wm_api_url, path))
+    }
+
+    /// 带`Platform` header的`warframe.market`的GET请求.
+    pub(crate) fn wm_platform_get(&self, platform: Platform, path: &str) -> RequestBuilder {
+        self.wm_get(path).header("Platform", platform.market())
+    }
+
+    /// 发送请求并把响应解码为`T`, 按状态码和解码结果区分错误.
+    pub(crate) async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> WfApiResult<T> {
+        let request = request.build()?;
+        if request.url().as_str().starts_with(self.wm_api_url.as_str()) {
+            self.wm_throttle().await;
+        }
+        let resp = self.client.execute(request).await?;
+
+        match resp.status() {
+            StatusCode::NOT_FOUND => return Err(WfApiError::NotFound),
+            StatusCode::TOO_MANY_REQUESTS => {
+                let retry_after = resp
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse().ok())
+                    .map(Duration::from_secs);
+                return Err(WfApiError::RateLimited { retry_after });
+            }
+            status if !status.is_success() => return Err(WfApiError::Status(status)),
+            _ => {}
+        }
+
+        let body = resp.text().await?;
+        serde_json::from_str(&body).map_err(|err| WfApiError::decode(err, &body))
+    }
+
+    /// 所有warframe.market请求排队, 保证间隔不小于`wm_interval`.
+    async fn wm_throttle(&self) {
+        let mut next = self.wm_next.lock().await;
+        let start = (*next).max(Instant::now());
+        tokio::time::sleep_until(start.into()).await;
+        *next = start + self.wm_interval;
+    }
+
+    /// 带缓存的`send_json`, `key`要能唯一确定这个请求(endpoint+参数), `ttl`由响应决定缓存多久.
+    pub(crate) async fn cached_json<T, F>(
+        &self,
+        key: &str,
+        ttl: F,
+        request: RequestBuilder,
+    ) -> WfApiResult<T>
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+        F: FnOnce(&T) -> Duration,
+    {
+        self.cache
+            .get_or_fetch(key, ttl, self.send_json(request))
+            .await
+            .map(|value| T::clone(&value))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WarframeApiBuilder {
+    api_url: CompactStr,
+    wm_api_url: CompactStr,
+    timeout: Duration,
+    wm_rate_limit: u32,
+    user_agent: Option<CompactStr>,
+    headers: HeaderMap,
+}
+
+impl Default for WarframeApiBuilder {
+    fn default() -> Self {
+        WarframeApiBuilder {
+            api_url: CompactStr::new(DEFAULT_API_URL),
+            wm_api_url: CompactStr::new(DEFAULT_WM_API_URL),
+            timeout: DEFAULT_TIMEOUT,
+            wm_rate_limit: DEFAULT_WM_RATE_LIMIT,
+            user_agent: None,
+            headers: HeaderMap::new(),
+        }
+    }
+}
+
+impl WarframeApiBuilder {
+    pub fn api_url(mut self, url: impl AsRef<str>) -> Self {
+        self.api_url = CompactStr::new(url.as_ref().trim_end_matches('/'));
+        self
+    }
+
+    pub fn wm_api_url(mut self, url: impl AsRef<str>) -> Self {
+        self.wm_api_url = CompactStr::new(url.as_ref().trim_end_matches('/'));
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// warframe.market每秒最多发出的请求数, 0为不限制.
+    pub fn wm_rate_limit(mut self, per_sec: u32) -> Self {
+        self.wm_rate_limit = per_sec;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl AsRef<str>) -> Self {
+        self.user_agent = Some(CompactStr::new(user_agent.as_ref()));
+        self
+    }
+
+    /// 添加一个附加到所有请求上的header.
+    pub fn header(mut self, name: &str, value: &str) -> anyhow::Result<Self> {
+        self.headers
+            .insert(HeaderName::from_str(name)?, HeaderValue::from_str(value)?);
+        Ok(self)
+    }
+
+    pub fn build(self) -> anyhow::Result<WarframeApi> {
+        let mut builder = reqwest::ClientBuilder::new()
+            .timeout(self.timeout)
+            .default_headers(self.headers);
+        if let Some(ua) = self.user_agent {
+            builder = builder.user_agent(ua.as_str());
+        }
+
+        Ok(WarframeApi {
+            client: builder.build()?,
+            cache: Cache::default(),
+            api_url: self.api_url,
+            wm_api_url: self.wm_api_url,
+            wm_interval: match self.wm_rate_limit {
+                0 => Duration::ZERO,
+                per_sec => Duration::from_secs(1) / per_sec,
+            },
+            wm_next: Arc::new(tokio::sync::Mutex::new(Instant::now())),
+        })
+    }
+}
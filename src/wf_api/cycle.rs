@@ -0,0 +1,314 @@
+use compact_str::CompactStr;
+use proc_qq::re_exports::ricq::msg::MessageChain;
+use proc_qq::MessageChainParseTrait;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::wf_api::{cache, Platform, WarframeApi, WfApiResult};
+
+/// 有昼夜之类循环的地区
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum World {
+    Cetus,
+    Vallis,
+    Cambion,
+    Earth,
+    Zariman,
+}
+
+impl World {
+    pub const ALL: &'static [World] = &[
+        World::Cetus,
+        World::Vallis,
+        World::Cambion,
+        World::Earth,
+        World::Zariman,
+    ];
+
+    fn path(&self) -> &'static str {
+        match self {
+            World::Cetus => "/cetusCycle",
+            World::Vallis => "/vallisCycle",
+            World::Cambion => "/cambionCycle",
+            World::Earth => "/earthCycle",
+            World::Zariman => "/zarimanCycle",
+        }
+    }
+
+    pub fn chinese(&self) -> &'static str {
+        match self {
+            World::Cetus => "希图斯",
+            World::Vallis => "奥布山谷",
+            World::Cambion => "魔胎之境",
+            World::Earth => "地球",
+            World::Zariman => "扎里曼",
+        }
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            World::Cetus => &["希图斯", "夜灵平原", "平原", "cetus", "plains"],
+            World::Vallis => &["奥布山谷", "山谷", "金星", "vallis", "orbvallis"],
+            World::Cambion => &["魔胎之境", "魔胎", "火卫二", "cambion", "deimos"],
+            World::Earth => &["地球", "earth"],
+            World::Zariman => &["扎里曼", "zariman"],
+        }
+    }
+
+    pub fn from_alias(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|w| w.aliases().contains(&name.as_str()))
+    }
+
+    pub fn states(&self) -> [CycleState; 2] {
+        match self {
+            World::Cetus | World::Earth => [CycleState::Day, CycleState::Night],
+            World::Vallis => [CycleState::Warm, CycleState::Cold],
+            World::Cambion => [CycleState::Fass, CycleState::Vome],
+            World::Zariman => [CycleState::Corpus, CycleState::Grineer],
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "CompactStr")]
+pub enum CycleState {
+    Day,
+    Night,
+    Warm,
+    Cold,
+    Fass,
+    Vome,
+    Corpus,
+    Grineer,
+    Unknown(CompactStr),
+}
+
+impl CycleState {
+    pub fn chinese(&self) -> &str {
+        match self {
+            CycleState::Day => "白天",
+            CycleState::Night => "黑夜",
+            CycleState::Warm => "温暖",
+            CycleState::Cold => "寒冷",
+            CycleState::Fass => "fass",
+            CycleState::Vome => "vome",
+            CycleState::Corpus => "c佬",
+            CycleState::Grineer => "g佬",
+            CycleState::Unknown(key) => key,
+        }
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            CycleState::Day => &["白天", "白昼", "day"],
+            CycleState::Night => &["黑夜", "夜晚", "night"],
+            CycleState::Warm => &["温暖", "暖", "warm"],
+            CycleState::Cold => &["寒冷", "冷", "cold"],
+            CycleState::Fass => &["fass", "法斯"],
+            CycleState::Vome => &["vome", "沃姆"],
+            CycleState::Corpus => &["c佬", "corpus"],
+            CycleState::Grineer => &["g佬", "grineer"],
+            CycleState::Unknown(_) => &[],
+        }
+    }
+
+    /// 循环只有两个状态, 当前状态结束后就是另一个. 不认识的状态不知道下一个是什么, 返回`None`.
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            CycleState::Day => Some(CycleState::Night),
+            CycleState::Night => Some(CycleState::Day),
+            CycleState::Warm => Some(CycleState::Cold),
+            CycleState::Cold => Some(CycleState::Warm),
+            CycleState::Fass => Some(CycleState::Vome),
+            CycleState::Vome => Some(CycleState::Fass),
+            CycleState::Corpus => Some(CycleState::Grineer),
+            CycleState::Grineer => Some(CycleState::Corpus),
+            CycleState::Unknown(_) => None,
+        }
+    }
+}
+
+impl From<CompactStr> for CycleState {
+    fn from(key: CompactStr) -> Self {
+        match key.to_lowercase().as_str() {
+            "day" => CycleState::Day,
+            "night" => CycleState::Night,
+            "warm" => CycleState::Warm,
+            "cold" => CycleState::Cold,
+            "fass" => CycleState::Fass,
+            "vome" => CycleState::Vome,
+            "corpus" => CycleState::Corpus,
+            "grineer" => CycleState::Grineer,
+            _ => CycleState::Unknown(key),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WorldCycle {
+    #[serde(default)]
+    pub id: CompactStr,
+
+    #[serde(with = "time::serde::iso8601")]
+    pub expiry: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub activation: OffsetDateTime,
+
+    pub state: CycleState,
+}
+
+impl WarframeApi {
+    pub async fn world_cycle(&self, platform: Platform, world: World) -> WfApiResult<WorldCycle> {
+        let path = world.path();
+        self.cached_json(
+            &format!("api:{}{}", platform.worldstate(), path),
+            |c: &WorldCycle| cache::until_expiry(c.expiry),
+            self.api_get(platform, path),
+        )
+        .await
+    }
+}
+
+pub fn gen_cycle_info(data: &WorldCycle) -> MessageChain {
+    // minutes
+    let remaining = (data.expiry - OffsetDateTime::now_utc()).whole_minutes();
+    format!(
+        "目前状态: {state} \n剩余时间(约): {time} 分钟",
+        state = data.state.chinese(),
+        time = remaining,
+    )
+    .parse_message_chain()
+}
+
+pub fn gen_cycle_line(world: World, data: &WorldCycle) -> String {
+    let remaining = (data.expiry - OffsetDateTime::now_utc()).whole_minutes();
+    match data.state.next() {
+        Some(next) => format!(
+            "{world}: {state}, {time} 分钟后{next}",
+            world = world.chinese(),
+            state = data.state.chinese(),
+            time = remaining,
+            next = next.chinese(),
+        ),
+        None => format!(
+            "{world}: {state}, 剩余 {time} 分钟",
+            world = world.chinese(),
+            state = data.state.chinese(),
+            time = remaining,
+        ),
+    }
+}
+
+/// 周期提醒的过滤条件, 如`山谷 温暖 5`表示奥布山谷温暖前5分钟提醒.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleAlert {
+    pub world: World,
+    pub state: CycleState,
+    pub minutes: i64,
+}
+
+impl CycleAlert {
+    const DEFAULT_MINUTES: i64 = 10;
+
+    pub fn parse(filter: &str) -> Option<Self> {
+        let mut world = None;
+        let mut state = None;
+        let mut minutes = None;
+
+        for word in filter.split_whitespace() {
+            let word = word.to_lowercase();
+            let number = word.trim_end_matches("分钟").trim_end_matches("min");
+            if let Ok(n) = number.parse::<i64>() {
+                minutes = Some(n);
+            } else if let Some(w) = World::from_alias(&word) {
+                world = Some(w);
+            } else if let Some(s) = World::ALL
+                .iter()
+                .flat_map(|w| w.states())
+                .find(|s| s.aliases().contains(&word.as_str()))
+            {
+                state = Some(s);
+            } else if word != "in" && word != "前" {
+                return None;
+            }
+        }
+
+        let world = world?;
+        let state = state?;
+        world.states().contains(&state).then(|| CycleAlert {
+            world,
+            state,
+            minutes: minutes.unwrap_or(Self::DEFAULT_MINUTES),
+        })
+    }
+
+    /// 规范化后的过滤条件, 用于储存
+    pub fn to_filter(&self) -> String {
+        format!(
+            "{} {} {}分钟",
+            self.world.chinese(),
+            self.state.chinese(),
+            self.minutes
+        )
+    }
+
+    /// 当前状态结束后就是`state`, 且剩余时间在提醒范围内.
+    pub fn triggered(&self, data: &WorldCycle) -> bool {
+        let remaining = (data.expiry - OffsetDateTime::now_utc()).whole_seconds();
+        data.state.next().as_ref() == Some(&self.state)
+            && remaining > 0
+            && remaining <= self.minutes * 60
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_unknown_state() {
+        let cycle: WorldCycle = serde_json::from_str(
+            r#"{
+                "id": "cetusCycle1",
+                "expiry": "2022-03-01T00:00:00.000Z",
+                "activation": "2022-02-28T22:00:00.000Z",
+                "state": "unknown"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(cycle.state, CycleState::Unknown("unknown".into()));
+        assert_eq!(cycle.state.chinese(), "unknown");
+        assert_eq!(cycle.state.next(), None);
+    }
+
+    #[test]
+    fn parse_cycle_alert() {
+        assert_eq!(
+            CycleAlert::parse("山谷 温暖 5"),
+            Some(CycleAlert {
+                world: World::Vallis,
+                state: CycleState::Warm,
+                minutes: 5,
+            })
+        );
+        assert_eq!(
+            CycleAlert::parse("平原 黑夜"),
+            Some(CycleAlert {
+                world: World::Cetus,
+                state: CycleState::Night,
+                minutes: CycleAlert::DEFAULT_MINUTES,
+            })
+        );
+        assert_eq!(
+            CycleAlert::parse("night in 15min cetus").map(|alert| alert.minutes),
+            Some(15)
+        );
+        assert_eq!(CycleAlert::parse("平原 温暖"), None);
+        assert_eq!(CycleAlert::parse("平原 黑夜 foo"), None);
+        assert_eq!(CycleAlert::parse("黑夜"), None);
+    }
+}
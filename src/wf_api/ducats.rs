@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use compact_str::CompactStr;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::wf_api::{
+    item_meta_complete, matching_orders, ItemMeta, OrderFilter, OrderType, Platform, WarframeApi,
+    WfApiError, ITEM_META_TREE,
+};
+
+/// 物品数据库中的tree, key为平台(`Platform::market`), value为json格式的`DucatRanking`.
+pub const DUCAT_RANKINGS_TREE: &str = "ducat_rankings";
+
+/// 一个部件的杜卡特效率
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DucatEntry {
+    pub url_name: CompactStr,
+    pub item_name: CompactStr,
+    pub ducats: i32,
+    /// 游戏中卖家的最低价
+    pub platinum: i32,
+    /// 游戏中的卖家数
+    pub sellers: usize,
+}
+
+impl DucatEntry {
+    /// 每白金换到的杜卡特
+    pub fn ducats_per_platinum(&self) -> f64 {
+        self.ducats as f64 / self.platinum.max(1) as f64
+    }
+}
+
+/// 一个平台的杜卡特效率排行, 按`ducats_per_platinum`从高到低.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DucatRanking {
+    #[serde(with = "time::serde::timestamp")]
+    pub updated: OffsetDateTime,
+    pub entries: Vec<DucatEntry>,
+}
+
+impl WarframeApi {
+    /// 逐个请求有杜卡特价值的物品(见`update_item_meta`)的订单, 计算排行并储存到`DUCAT_RANKINGS_TREE`.
+    /// 请求出错的物品会被跳过. 物品信息还不全(见`item_meta_complete`)时不计算, 返回`None`.
+    pub async fn update_ducat_ranking(
+        &self,
+        platform: Platform,
+        db: Arc<sled::Db>,
+    ) -> anyhow::Result<Option<DucatRanking>> {
+        if !item_meta_complete(&db)? {
+            return Ok(None);
+        }
+
+        let mut parts = Vec::new();
+        for meta in db.open_tree(ITEM_META_TREE)?.iter().values() {
+            let meta = serde_json::from_slice::<ItemMeta>(meta?.as_ref())?;
+            if meta.ducats.is_some() && meta.parts.is_empty() {
+                parts.push(meta);
+            }
+        }
+        if parts.is_empty() {
+            return Ok(None);
+        }
+
+        let filter = OrderFilter::default();
+        let mut entries = Vec::with_capacity(parts.len());
+        for part in parts {
+            let orders = match self.wm_item(platform, &part.url_name).await {
+                Ok(orders) => orders,
+                Err(WfApiError::NotFound) => continue,
+                Err(err) => {
+                    tracing::warn!("ducat ranking {} err: {}", part.url_name, err);
+                    continue;
+                }
+            };
+            let prices = matching_orders(&orders, &OrderType::Sell, None, &filter)
+                .map(|order| order.platinum)
+                .collect::<Vec<_>>();
+            if let Some(platinum) = prices.iter().copied().min() {
+                entries.push(DucatEntry {
+                    url_name: part.url_name,
+                    item_name: part.item_name,
+                    ducats: part.ducats.unwrap_or_default(),
+                    platinum,
+                    sellers: prices.len(),
+                });
+            }
+        }
+        entries.sort_by(|l, r| {
+            r.ducats_per_platinum()
+                .total_cmp(&l.ducats_per_platinum())
+                .then_with(|| r.ducats.cmp(&l.ducats))
+        });
+
+        let ranking = DucatRanking {
+            updated: OffsetDateTime::now_utc(),
+            entries,
+        };
+        db.open_tree(DUCAT_RANKINGS_TREE)?
+            .insert(platform.market(), serde_json::to_vec(&ranking)?)?;
+
+        Ok(Some(ranking))
+    }
+}
+
+/// 卖家数不少于`min_sellers`的前`count`个部件
+pub fn gen_ducat_ranking(
+    platform: Platform,
+    ranking: &DucatRanking,
+    min_sellers: usize,
+    count: usize,
+) -> String {
+    let entries = ranking
+        .entries
+        .iter()
+        .filter(|entry| entry.sellers >= min_sellers)
+        .take(count)
+        .enumerate()
+        .map(|(i, entry)| {
+            format!(
+                "{}. {}: {} 杜卡特 / {} 白金 = {:.1} ({} 个卖家)",
+                i + 1,
+                entry.item_name,
+                entry.ducats,
+                entry.platinum,
+                entry.ducats_per_platinum(),
+                entry.sellers
+            )
+        })
+        .join("\n");
+    let minutes = (OffsetDateTime::now_utc() - ranking.updated).whole_minutes();
+    if entries.is_empty() {
+        format!(
+            "{} 没有卖家不少于 {} 个的部件",
+            platform.name(),
+            min_sellers
+        )
+    } else {
+        format!(
+            "{} 杜卡特效率 (卖家不少于 {} 个, {} 分钟前更新):\n{}",
+            platform.name(),
+            min_sellers,
+            minutes,
+            entries
+        )
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use compact_str::CompactStr;
+use reqwest::StatusCode;
+
+/// 解码失败时保留的响应体长度(字符数).
+const BODY_SNIPPET_LEN: usize = 200;
+
+pub type WfApiResult<T> = Result<T, WfApiError>;
+
+/// 可以clone, 方便缓存把同一个错误分享给所有等待中的请求.
+#[derive(Debug, Clone)]
+pub enum WfApiError {
+    /// 连接失败, dns错误等
+    Network(Arc<reqwest::Error>),
+    Timeout,
+    /// 404, 物品/接口不存在
+    NotFound,
+    /// 429, 被限流
+    RateLimited {
+        retry_after: Option<Duration>,
+    },
+    /// 其他非2xx的状态码
+    Status(StatusCode),
+    /// 响应不是预期的json结构, `body`为截断后的原始响应
+    Decode {
+        error: Arc<serde_json::Error>,
+        body: CompactStr,
+    },
+}
+
+impl WfApiError {
+    pub(crate) fn decode(error: serde_json::Error, body: &str) -> Self {
+        let mut snippet = body.chars().take(BODY_SNIPPET_LEN).collect::<String>();
+        if body.chars().nth(BODY_SNIPPET_LEN).is_some() {
+            snippet.push_str("...");
+        }
+        WfApiError::Decode {
+            error: Arc::new(error),
+            body: CompactStr::new(snippet),
+        }
+    }
+}
+
+impl From<reqwest::Error> for WfApiError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            WfApiError::Timeout
+        } else {
+            WfApiError::Network(Arc::new(err))
+        }
+    }
+}
+
+impl fmt::Display for WfApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WfApiError::Network(err) => write!(f, "network error: {}", err),
+            WfApiError::Timeout => write!(f, "request timed out"),
+            WfApiError::NotFound => write!(f, "not found"),
+            WfApiError::RateLimited {
+                retry_after: Some(after),
+            } => write!(f, "rate limited, retry after {}s", after.as_secs()),
+            WfApiError::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            WfApiError::Status(status) => write!(f, "unexpected status: {}", status),
+            WfApiError::Decode { error, body } => {
+                write!(f, "decode error: {}, body: {}", error, body)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WfApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WfApiError::Network(err) => Some(err.as_ref()),
+            WfApiError::Decode { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
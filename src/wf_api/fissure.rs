@@ -0,0 +1,198 @@
+use compact_str::CompactStr;
+use itertools::Itertools;
+use proc_qq::re_exports::ricq::msg::MessageChain;
+use proc_qq::MessageChainParseTrait;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::wf_api::{cache, Enemy, Platform, WarframeApi, WfApiResult};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Fissure {
+    pub id: CompactStr,
+    #[serde(with = "time::serde::iso8601")]
+    pub activation: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub expiry: OffsetDateTime,
+
+    pub node: CompactStr,
+    #[serde(rename = "nodeKey")]
+    pub node_key: Option<CompactStr>,
+
+    #[serde(rename = "missionType")]
+    pub mission_type: CompactStr,
+    #[serde(rename = "missionTypeKey")]
+    pub mission_type_key: CompactStr,
+
+    pub enemy: Enemy,
+
+    pub tier: CompactStr,
+    #[serde(rename = "tierNum")]
+    pub tier_num: u8,
+
+    #[serde(default)]
+    pub expired: bool,
+    /// 九重天(虚空风暴)
+    #[serde(rename = "isStorm", default)]
+    pub is_storm: bool,
+    /// 钢铁之路
+    #[serde(rename = "isHard", default)]
+    pub is_hard: bool,
+}
+
+impl Fissure {
+    pub fn tier_nickname(&self) -> &str {
+        match self.tier_num {
+            1 => "古纪",
+            2 => "前纪",
+            3 => "中纪",
+            4 => "后纪",
+            5 => "安魂",
+            6 => "全能",
+            _ => &self.tier,
+        }
+    }
+
+    fn tier_english(&self) -> &str {
+        match self.tier_num {
+            1 => "lith",
+            2 => "meso",
+            3 => "neo",
+            4 => "axi",
+            5 => "requiem",
+            6 => "omnia",
+            _ => "",
+        }
+    }
+
+    pub fn markers(&self) -> &'static str {
+        match (self.is_hard, self.is_storm) {
+            (true, _) => " [钢铁]",
+            (_, true) => " [九重天]",
+            _ => "",
+        }
+    }
+
+    /// 过滤条件以空格分割, 每一项都要能在纪元/任务类型/敌人/节点/模式中找到,
+    /// 如`Axi Survival`, `Steel Path Capture`, `九重天 前纪`.
+    pub fn matches(&self, filter: &str) -> bool {
+        let mut desc = format!(
+            "{} {} {} {} {} {} {} {}",
+            self.tier,
+            self.tier_english(),
+            self.tier_nickname(),
+            self.mission_type_key,
+            self.mission_type,
+            self.node,
+            self.node_key.as_deref().unwrap_or_default(),
+            self.enemy.key(),
+        );
+        if self.is_hard {
+            desc.push_str(" steelpath sp 钢铁之路");
+        } else if self.is_storm {
+            desc.push_str(" voidstorm storm 九重天 虚空风暴");
+        } else {
+            desc.push_str(" normal 普通");
+        }
+        let desc = desc.to_lowercase().replace(' ', "");
+
+        filter
+            .to_lowercase()
+            .replace("steel path", "steelpath")
+            .replace("void storm", "voidstorm")
+            .split_whitespace()
+            .all(|word| desc.contains(word))
+    }
+}
+
+impl WarframeApi {
+    pub async fn fissures(&self, platform: Platform) -> WfApiResult<Vec<Fissure>> {
+        let path = "/fissures?language=zh";
+        self.cached_json(
+            &format!("api:{}{}", platform.worldstate(), path),
+            |fissures: &Vec<Fissure>| cache::until_earliest(fissures.iter().map(|f| f.expiry)),
+            self.api_get(platform, path),
+        )
+        .await
+    }
+}
+
+pub fn gen_fissure_line(data: &Fissure) -> String {
+    // minutes
+    let remaining = (data.expiry - OffsetDateTime::now_utc()).whole_minutes();
+    format!(
+        "{tier} {ty} - {node} ({enemy}) 剩余 {time} 分钟{markers}",
+        tier = data.tier_nickname(),
+        ty = data.mission_type,
+        node = data.node,
+        enemy = data.enemy.nickname(),
+        time = remaining,
+        markers = data.markers(),
+    )
+}
+
+/// 按纪元分组列出裂缝.
+pub fn gen_fissures_info<'a>(data: impl IntoIterator<Item = &'a Fissure>) -> MessageChain {
+    let mut info = String::with_capacity(1024);
+    data.into_iter()
+        .filter(|f| !f.expired)
+        .sorted_by_key(|f| (f.tier_num, f.is_hard, f.is_storm, f.expiry))
+        .group_by(|f| f.tier_num)
+        .into_iter()
+        .for_each(|(_, fissures)| {
+            let mut fissures = fissures.peekable();
+            if let Some(first) = fissures.peek() {
+                info.push_str(&format!("== {} ==\n", first.tier_nickname()));
+            }
+            fissures.for_each(|f| {
+                info.push_str(&gen_fissure_line(f));
+                info.push('\n');
+            });
+        });
+
+    if info.is_empty() {
+        info.push_str("现在没有符合条件的裂缝");
+    }
+    info.trim_end().parse_message_chain()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fissure(tier_num: u8, mission: &str, is_hard: bool, is_storm: bool) -> Fissure {
+        serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "activation": "2022-03-01T00:00:00.000Z",
+            "expiry": "2022-03-01T01:00:00.000Z",
+            "node": "Ukko (Void)",
+            "nodeKey": "Ukko (Void)",
+            "missionType": mission,
+            "missionTypeKey": mission,
+            "enemy": "Corrupted",
+            "tier": "Axi",
+            "tierNum": tier_num,
+            "isHard": is_hard,
+            "isStorm": is_storm,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn fissure_filters() {
+        let normal = fissure(4, "Survival", false, false);
+        assert!(normal.matches("Axi Survival"));
+        assert!(normal.matches("后纪 普通"));
+        assert!(!normal.matches("Steel Path"));
+        assert!(!normal.matches("Lith Survival"));
+
+        let hard = fissure(4, "Capture", true, false);
+        assert!(hard.matches("Steel Path Capture"));
+        assert!(hard.matches("钢铁之路"));
+        assert!(!hard.matches("九重天"));
+
+        let storm = fissure(2, "Skirmish", false, true);
+        assert!(storm.matches("九重天 前纪"));
+        assert!(storm.matches("void storm"));
+    }
+}
@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use compact_str::CompactStr;
+use proc_qq::re_exports::ricq::msg::MessageChain;
+use proc_qq::MessageChainParseTrait;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::wf_api::{Enemy, Platform, WarframeApi, WfApiResult};
+
+/// 入侵在worldstate中没有结束时间, 缓存固定时间.
+const INVASIONS_TTL: Duration = Duration::from_secs(60);
+
+/// 订阅入侵提醒但没有指定奖励时使用的默认关注列表
+pub const DEFAULT_INVASION_WATCH: &[&str] = &[
+    "Orokin Catalyst",
+    "Orokin Reactor",
+    "Forma",
+    "Wraith",
+    "Vandal",
+    "催化剂",
+    "反应堆",
+    "福马",
+    "亡魂",
+    "破坏者",
+];
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CountedItem {
+    pub count: i32,
+    #[serde(rename = "type")]
+    pub r#type: CompactStr,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct InvasionReward {
+    #[serde(default)]
+    pub items: Vec<CompactStr>,
+    #[serde(rename = "countedItems", default)]
+    pub counted_items: Vec<CountedItem>,
+    #[serde(rename = "asString", default)]
+    pub as_string: CompactStr,
+}
+
+impl InvasionReward {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.counted_items.is_empty()
+    }
+
+    /// 奖励中是否有物品名包含`keyword`(不区分大小写和空格)
+    pub fn contains(&self, keyword: &str) -> bool {
+        let keyword = keyword.to_lowercase().replace(' ', "");
+        self.items
+            .iter()
+            .map(|item| item.as_str())
+            .chain(self.counted_items.iter().map(|item| item.r#type.as_str()))
+            .any(|name| name.to_lowercase().replace(' ', "").contains(&keyword))
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct InvasionSide {
+    #[serde(default)]
+    pub reward: Option<InvasionReward>,
+    #[serde(rename = "factionKey")]
+    pub faction: Enemy,
+}
+
+impl InvasionSide {
+    pub fn reward_string(&self) -> &str {
+        match &self.reward {
+            Some(reward) if !reward.is_empty() => &reward.as_string,
+            _ => "无奖励",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Invasion {
+    pub id: CompactStr,
+    #[serde(with = "time::serde::iso8601")]
+    pub activation: OffsetDateTime,
+
+    pub node: CompactStr,
+    pub desc: Option<CompactStr>,
+    pub attacker: InvasionSide,
+    pub defender: InvasionSide,
+    #[serde(rename = "vsInfestation", default)]
+    pub vs_infestation: bool,
+
+    /// 进攻方的进度, 0~100
+    pub completion: f64,
+    #[serde(default)]
+    pub completed: bool,
+}
+
+impl Invasion {
+    pub fn rewards(&self) -> impl Iterator<Item = &InvasionReward> {
+        self.attacker
+            .reward
+            .iter()
+            .chain(self.defender.reward.iter())
+    }
+
+    /// 任意一方的奖励中有`keyword`
+    pub fn offers(&self, keyword: &str) -> bool {
+        self.rewards().any(|reward| reward.contains(keyword))
+    }
+}
+
+impl WarframeApi {
+    pub async fn invasions(&self, platform: Platform) -> WfApiResult<Vec<Invasion>> {
+        let path = "/invasions?language=zh";
+        self.cached_json(
+            &format!("api:{}{}", platform.worldstate(), path),
+            |_| INVASIONS_TTL,
+            self.api_get(platform, path),
+        )
+        .await
+    }
+}
+
+pub fn gen_invasion_line(data: &Invasion) -> String {
+    format!(
+        "{node} {attacker}({attacker_reward}) vs {defender}({defender_reward}) 进度 {completion:.1}%",
+        node = data.node,
+        attacker = data.attacker.faction.nickname(),
+        attacker_reward = data.attacker.reward_string(),
+        defender = data.defender.faction.nickname(),
+        defender_reward = data.defender.reward_string(),
+        completion = data.completion,
+    )
+}
+
+pub fn gen_invasions_info<'a>(data: impl IntoIterator<Item = &'a Invasion>) -> MessageChain {
+    let info = data
+        .into_iter()
+        .filter(|i| !i.completed)
+        .map(gen_invasion_line)
+        .collect::<Vec<_>>();
+    if info.is_empty() {
+        "现在没有进行中的入侵".parse_message_chain()
+    } else {
+        info.join("\n").parse_message_chain()
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use compact_str::CompactStr;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+use crate::wf_api::{WarframeApi, WfApiError};
+
+/// 物品数据库中的tree, key为物品的`url_name`, value为json格式的`ItemMeta`.
+pub const ITEM_META_TREE: &str = "item_meta";
+/// 物品数据库中的tree, key为套装的`url_name`, value为json格式的`Vec<SetPart>`.
+pub const ITEM_SETS_TREE: &str = "item_sets";
+/// 物品数据库中的tree, key为部件的`url_name`, value为所属套装的`url_name`.
+pub const PART_SETS_TREE: &str = "part_sets";
+
+/// 物品数据库中的tree, `update_item_meta`所有请求都成功时在`META_COMPLETE_KEY`写入完成时间(大端序的unix时间戳).
+pub const ITEM_META_STATUS_TREE: &str = "item_meta_status";
+const META_COMPLETE_KEY: &str = "complete";
+
+/// 物品信息储存超过这么久会重新请求, 入库状态等会变.
+const ITEM_META_TTL: Duration = Duration::days(7);
+/// 每个物品的有效期按`url_name`再多0到这么久, 避免第一次储存的物品同时过期.
+const ITEM_META_TTL_SPREAD: Duration = Duration::days(3);
+
+/// 同时只进行一次`update_item_meta`
+static META_LOCK: Lazy<tokio::sync::Mutex<()>> = Lazy::new(Default::default);
+
+/// 套装中的一个部件
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetPart {
+    pub url_name: CompactStr,
+    pub item_name: CompactStr,
+    /// 一套需要的数量
+    pub quantity: i32,
+}
+
+/// 物品的详细信息
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ItemMeta {
+    pub url_name: CompactStr,
+    pub item_name: CompactStr,
+    pub en_name: CompactStr,
+    #[serde(default)]
+    pub tags: Vec<CompactStr>,
+    pub ducats: Option<i32>,
+    /// mod和赋能的满级
+    pub max_rank: Option<i32>,
+    /// 所属套装的`url_name`
+    pub set: Option<CompactStr>,
+    /// 套装的部件, 只有套装有
+    #[serde(default)]
+    pub parts: Vec<SetPart>,
+    /// 是否已入库, 只有prime物品和遗物有
+    pub vaulted: Option<bool>,
+    #[serde(with = "time::serde::timestamp")]
+    pub updated: OffsetDateTime,
+}
+
+/// 64位FNV-1a, 结果不随Rust版本变化, 换工具链后物品的有效期不会被打乱.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// 物品信息的有效期, `ITEM_META_TTL`加上按`url_name`散列的0到`ITEM_META_TTL_SPREAD`.
+fn item_meta_ttl(url_name: &str) -> Duration {
+    let spread = ITEM_META_TTL_SPREAD.whole_seconds() as u64;
+    ITEM_META_TTL + Duration::seconds((fnv1a(url_name.as_bytes()) % spread) as i64)
+}
+
+/// 储存过且没有过期
+fn is_fresh(meta: &sled::Tree, url_name: &str) -> anyhow::Result<bool> {
+    Ok(match meta.get(url_name.as_bytes())? {
+        Some(value) => serde_json::from_slice::<ItemMeta>(value.as_ref())
+            .map(|meta| OffsetDateTime::now_utc() - meta.updated < item_meta_ttl(url_name))
+            .unwrap_or(false),
+        None => false,
+    })
+}
+
+/// 是否有过一次所有请求都成功的`update_item_meta`, 在那之前储存的物品信息不全.
+pub fn item_meta_complete(db: &sled::Db) -> sled::Result<bool> {
+    db.open_tree(ITEM_META_STATUS_TREE)?
+        .contains_key(META_COMPLETE_KEY)
+}
+
+/// 删除`tree`中key不在`url_names`里的数据
+fn retain_items(tree: &sled::Tree, url_names: &HashSet<&str>) -> sled::Result<()> {
+    for key in tree.iter().keys() {
+        let key = key?;
+        if !url_names.contains(String::from_utf8_lossy(key.as_ref()).as_ref()) {
+            tree.remove(key)?;
+        }
+    }
+    Ok(())
+}
+
+impl WarframeApi {
+    /// 逐个请求目录(见`update_items_db`)中缺少或过期的物品, 储存`ItemMeta`, 套装的部件和部件所属的套装.
+    /// 套装先请求, 它的部件随套装一起储存, 不用再单独请求. 已不存在的物品会被删除. 返回请求的物品数.
+    /// 第一次要请求几千个物品, 应在后台调用.
+    pub async fn update_item_meta(&self, db: Arc<sled::Db>) -> anyhow::Result<u32> {
+        #[derive(Deserialize, Debug, Clone)]
+        struct Body {
+            payload: Payload,
+        }
+
+        #[derive(Deserialize, Debug, Clone)]
+        struct Payload {
+            item: Item,
+        }
+
+        #[derive(Deserialize, Debug, Clone)]
+        struct Item {
+            items_in_set: Vec<SetItem>,
+        }
+
+        #[derive(Deserialize, Debug, Clone)]
+        struct SetItem {
+            url_name: CompactStr,
+            #[serde(default)]
+            set_root: bool,
+            quantity_for_set: Option<i32>,
+            #[serde(default)]
+            tags: Vec<CompactStr>,
+            ducats: Option<i32>,
+            mod_max_rank: Option<i32>,
+            vaulted: Option<bool>,
+            en: ItemName,
+            #[serde(rename = "zh-hans")]
+            zh: Option<ItemName>,
+        }
+
+        #[derive(Deserialize, Debug, Clone)]
+        struct ItemName {
+            item_name: CompactStr,
+        }
+
+        let _guard = META_LOCK.lock().await;
+
+        let url_names = db
+            .iter()
+            .values()
+            .map(|url_name| Ok(CompactStr::new(String::from_utf8_lossy(url_name?.as_ref()))))
+            .collect::<sled::Result<HashSet<_>>>()?;
+
+        let meta = db.open_tree(ITEM_META_TREE)?;
+        let sets = db.open_tree(ITEM_SETS_TREE)?;
+        let part_sets = db.open_tree(PART_SETS_TREE)?;
+
+        let existing = url_names.iter().map(CompactStr::as_str).collect();
+        for tree in [&meta, &sets, &part_sets] {
+            retain_items(tree, &existing)?;
+        }
+
+        let mut pending = url_names.iter().collect::<Vec<_>>();
+        pending.sort_by_key(|url_name| !url_name.ends_with("_set"));
+
+        let mut num = 0u32;
+        let mut failed = 0u32;
+        for url_name in pending {
+            if is_fresh(&meta, url_name)? {
+                continue;
+            }
+            let item = match self
+                .send_json::<Body>(
+                    self.wm_get(&format!("/items/{}", url_name))
+                        .header("Language", "zh-hans"),
+                )
+                .await
+            {
+                Ok(body) => body.payload.item,
+                Err(WfApiError::NotFound) => continue,
+                Err(err) => {
+                    tracing::warn!("fetch item {} err: {}", url_name, err);
+                    failed += 1;
+                    continue;
+                }
+            };
+            num += 1;
+
+            let root = item
+                .items_in_set
+                .iter()
+                .find(|item| item.set_root)
+                .map(|item| item.url_name.clone());
+            let parts = item
+                .items_in_set
+                .iter()
+                .filter(|item| !item.set_root)
+                .map(|item| SetPart {
+                    url_name: item.url_name.clone(),
+                    item_name: item.zh.as_ref().unwrap_or(&item.en).item_name.clone(),
+                    quantity: item.quantity_for_set.unwrap_or(1),
+                })
+                .collect::<Vec<_>>();
+            if let Some(root) = root.as_ref().filter(|_| !parts.is_empty()) {
+                for part in &parts {
+                    part_sets.insert(part.url_name.as_bytes(), root.as_bytes())?;
+                }
+                sets.insert(root.as_bytes(), serde_json::to_vec(&parts)?)?;
+            }
+
+            let updated = OffsetDateTime::now_utc();
+            for set_item in item.items_in_set {
+                let item_meta = ItemMeta {
+                    item_name: set_item.zh.unwrap_or_else(|| set_item.en.clone()).item_name,
+                    en_name: set_item.en.item_name,
+                    tags: set_item.tags,
+                    ducats: set_item.ducats,
+                    max_rank: set_item.mod_max_rank,
+                    set: root.clone().filter(|_| !set_item.set_root),
+                    parts: if set_item.set_root {
+                        parts.clone()
+                    } else {
+                        Vec::new()
+                    },
+                    vaulted: set_item.vaulted,
+                    updated,
+                    url_name: set_item.url_name,
+                };
+                meta.insert(
+                    item_meta.url_name.as_bytes(),
+                    serde_json::to_vec(&item_meta)?,
+                )?;
+            }
+        }
+
+        // 有请求失败时信息还不全, 等下次更新补上
+        if failed == 0 {
+            db.open_tree(ITEM_META_STATUS_TREE)?.insert(
+                META_COMPLETE_KEY,
+                &OffsetDateTime::now_utc().unix_timestamp().to_be_bytes(),
+            )?;
+        } else {
+            tracing::warn!("item meta incomplete, {} items failed", failed);
+        }
+        db.flush_async().await?;
+
+        Ok(num)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a_is_stable() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn item_meta_ttl_within_spread() {
+        for url_name in ["ash_prime_set", "ash_prime_systems", "serration"] {
+            let ttl = item_meta_ttl(url_name);
+            assert!(ttl >= ITEM_META_TTL && ttl < ITEM_META_TTL + ITEM_META_TTL_SPREAD);
+        }
+    }
+}
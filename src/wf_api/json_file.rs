@@ -0,0 +1,54 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// 本地的JSON数据文件, `refresh`时文件修改过就重新读取, 读取失败时保留原来的值.
+pub(crate) struct JsonFile<T> {
+    path: PathBuf,
+    /// 上次读取时文件的修改时间, 文件不存在为`None`
+    modified: Option<SystemTime>,
+    pub value: T,
+}
+
+impl<T: Serialize + DeserializeOwned> JsonFile<T> {
+    /// 文件不存在时使用`default`.
+    pub fn new(path: impl Into<PathBuf>, default: T) -> Self {
+        let mut file = JsonFile {
+            path: path.into(),
+            modified: None,
+            value: default,
+        };
+        file.refresh();
+        file
+    }
+
+    pub fn refresh(&mut self) {
+        let modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        if modified == self.modified {
+            return;
+        }
+
+        if modified.is_some() {
+            let value = fs::read_to_string(&self.path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| Ok(serde_json::from_str(&json)?));
+            match value {
+                Ok(value) => {
+                    tracing::info!("loaded {}", self.path.display());
+                    self.value = value;
+                }
+                Err(err) => tracing::error!("load {} error: {}", self.path.display(), err),
+            }
+        }
+        self.modified = modified;
+    }
+
+    pub fn save(&mut self) -> anyhow::Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(&self.value)?)?;
+        self.modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        Ok(())
+    }
+}
@@ -0,0 +1,792 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use compact_str::CompactStr;
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use phf::phf_map;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+pub use arbitration::*;
+pub use bounty::*;
+pub use catalog::*;
+pub use client::{WarframeApi, WarframeApiBuilder, WF_API};
+pub use cycle::*;
+pub use ducats::*;
+pub use error::{WfApiError, WfApiResult};
+pub use fissure::*;
+pub use invasion::*;
+pub use item_meta::*;
+pub use nightwave::*;
+pub use platform::Platform;
+pub use riven::*;
+pub use sortie::*;
+pub use statistics::*;
+pub use void_trader::*;
+
+mod arbitration;
+mod bounty;
+mod cache;
+mod catalog;
+mod client;
+mod cycle;
+mod ducats;
+mod error;
+mod fissure;
+mod invasion;
+mod item_meta;
+mod json_file;
+mod nightwave;
+mod platform;
+mod riven;
+mod sortie;
+mod statistics;
+mod void_trader;
+
+/// 订单和紫卡拍卖变化很快, 只缓存一小会.
+const ORDERS_TTL: Duration = Duration::from_secs(60);
+
+/// 内置的词条简称, 更新词条时和用户别名一起加入词条名索引, 每个词条最多一个.
+/// 词条的中文名和`url_name`本身就在索引中, 不用写在这里.
+pub static RIVEN_ATTR: phf::Map<&'static str, &'static str> = phf_map! {
+    "弹药上限" => "ammo_maximum",
+    "c伤" => "damage_vs_corpus",
+    "g伤" => "damage_vs_grineer",
+    "i伤" => "damage_vs_infested",
+    "冰" => "cold_damage",
+    "初始连击" => "channeling_damage",
+    "连击时间" => "combo_duration",
+    "暴率" => "critical_chance",
+    "滑行暴率" => "critical_chance_on_slide_attack",
+    "暴伤" => "critical_damage",
+    "基伤" => "base_damage_/_melee_damage",
+    "电" => "electric_damage",
+    "火" => "heat_damage",
+    "处决" => "finisher_damage",
+    "攻速" => "fire_rate_/_attack_speed",
+    "投射物" => "projectile_speed",
+    "冲击" => "impact_damage",
+    "弹匣" => "magazine_capacity",
+    "多重" => "multishot",
+    "毒" => "toxin_damage",
+    "穿透" => "punch_through",
+    "穿刺" => "puncture_damage",
+    "装填" => "reload_speed",
+    "范围" => "range",
+    "切割" => "slash_damage",
+    "触发时间" => "status_duration",
+    "后坐力" => "recoil",
+    "变焦" => "zoom",
+    "额外连击" => "chance_to_gain_extra_combo_count",
+    "连击几率" => "chance_to_gain_combo_count",
+};
+
+/// `negative_stats`的特殊值, 不是词条: `无负`为没有负面词条, `负`为有任意负面词条.
+pub static NEGATIVE_STATS: phf::Map<&'static str, &'static str> = phf_map! {
+    "无负" => "none",
+    "负" => "has",
+};
+
+/// 派系, 不认识的保留原始名称, 不会导致整个响应解码失败.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "CompactStr", into = "CompactStr")]
+pub enum Enemy {
+    Orokin,
+    Corrupted,
+    Infested,
+    Corpus,
+    Grineer,
+    Tenno,
+    Sentient,
+    Narmer,
+    Murmur,
+    Scaldra,
+    Techrot,
+    Unknown(CompactStr),
+}
+
+impl Enemy {
+    pub const ALL: &'static [Enemy] = &[
+        Enemy::Orokin,
+        Enemy::Corrupted,
+        Enemy::Infested,
+        Enemy::Corpus,
+        Enemy::Grineer,
+        Enemy::Tenno,
+        Enemy::Sentient,
+        Enemy::Narmer,
+        Enemy::Murmur,
+        Enemy::Scaldra,
+        Enemy::Techrot,
+    ];
+
+    /// api中的英文名
+    pub fn key(&self) -> &str {
+        match self {
+            Enemy::Orokin => "Orokin",
+            Enemy::Corrupted => "Corrupted",
+            Enemy::Infested => "Infested",
+            Enemy::Corpus => "Corpus",
+            Enemy::Grineer => "Grineer",
+            Enemy::Tenno => "Tenno",
+            Enemy::Sentient => "Sentient",
+            Enemy::Narmer => "Narmer",
+            Enemy::Murmur => "Murmur",
+            Enemy::Scaldra => "Scaldra",
+            Enemy::Techrot => "Techrot",
+            Enemy::Unknown(key) => key,
+        }
+    }
+
+    /// 按英文名查找, 不区分大小写.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|e| e.key().eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    pub fn nickname(&self) -> &str {
+        match self {
+            Enemy::Orokin => "o佬",
+            Enemy::Corrupted => "堕落者",
+            Enemy::Infested => "i佬",
+            Enemy::Corpus => "c佬",
+            Enemy::Grineer => "g佬",
+            Enemy::Tenno => "天..天诺?",
+            Enemy::Sentient => "s佬",
+            Enemy::Narmer => "合一众",
+            Enemy::Murmur => "低语者",
+            Enemy::Scaldra => "Scaldra",
+            Enemy::Techrot => "Techrot",
+            Enemy::Unknown(key) => key,
+        }
+    }
+}
+
+impl From<CompactStr> for Enemy {
+    fn from(key: CompactStr) -> Self {
+        Self::ALL
+            .iter()
+            .find(|e| e.key() == key.as_str())
+            .cloned()
+            .unwrap_or(Enemy::Unknown(key))
+    }
+}
+
+impl From<Enemy> for CompactStr {
+    fn from(enemy: Enemy) -> Self {
+        CompactStr::new(enemy.key())
+    }
+}
+
+/// 以"x 天 x 小时"或"x 小时 x 分钟"表示的剩余时间.
+pub fn remaining_time(expiry: OffsetDateTime) -> String {
+    let remaining = (expiry - OffsetDateTime::now_utc()).whole_minutes();
+    if remaining >= 60 * 24 {
+        format!("{} 天 {} 小时", remaining / 60 / 24, remaining / 60 % 24)
+    } else {
+        format!("{} 小时 {} 分钟", remaining / 60, remaining % 60)
+    }
+}
+
+/// 物品和紫卡数据库同时只进行一次更新, 手动更新和定时更新可能撞上.
+static UPDATE_LOCK: Lazy<tokio::sync::Mutex<()>> = Lazy::new(Default::default);
+
+/// `update_items_db`的结果
+#[derive(Debug, Clone)]
+pub struct ItemsDbUpdate {
+    pub items: u32,
+    /// 有物品增减时的新版本
+    pub version: Option<CatalogVersion>,
+}
+
+impl WarframeApi {
+    /// 物品名和`url_name`各平台都一样, 所以物品数据库不分平台.
+    /// 替换物品名到`url_name`的映射, 见`replace_catalog`.
+    /// 物品的详细信息要逐个请求, 比较慢, 由调用者另外用`update_item_meta`在后台更新.
+    pub async fn update_items_db(&self, db: Arc<sled::Db>) -> anyhow::Result<ItemsDbUpdate> {
+        #[derive(Deserialize, Debug, Clone)]
+        struct Body {
+            payload: Payload,
+        }
+
+        #[derive(Deserialize, Debug, Clone)]
+        struct Payload {
+            items: Vec<Item>,
+        }
+
+        #[derive(Deserialize, Debug, Clone)]
+        struct Item {
+            url_name: CompactStr,
+            item_name: CompactStr,
+        }
+
+        let _guard = UPDATE_LOCK.lock().await;
+
+        let payload = self
+            .send_json::<Body>(self.wm_get("/items").header("Language", "zh-hans"))
+            .await?
+            .payload;
+
+        let items = payload
+            .items
+            .into_iter()
+            .map(|item| (item.item_name, item.url_name))
+            .collect::<Vec<_>>();
+        let version = replace_catalog(&db, &items)?;
+
+        db.flush_async().await?;
+
+        Ok(ItemsDbUpdate {
+            items: items.len() as u32,
+            version,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "CompactStr")]
+pub enum OrderType {
+    Sell,
+    Buy,
+    Unknown(CompactStr),
+}
+
+impl From<CompactStr> for OrderType {
+    fn from(key: CompactStr) -> Self {
+        match key.as_str() {
+            "sell" => OrderType::Sell,
+            "buy" => OrderType::Buy,
+            _ => OrderType::Unknown(key),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "CompactStr")]
+pub enum UserStatus {
+    InGame,
+    Online,
+    Offline,
+    Invisible,
+    Unknown(CompactStr),
+}
+
+impl UserStatus {
+    pub fn chinese(&self) -> &str {
+        match self {
+            UserStatus::InGame => "游戏中",
+            UserStatus::Online => "在线",
+            UserStatus::Offline => "离线",
+            UserStatus::Invisible => "隐身",
+            UserStatus::Unknown(key) => key,
+        }
+    }
+}
+
+impl From<CompactStr> for UserStatus {
+    fn from(key: CompactStr) -> Self {
+        match key.as_str() {
+            "ingame" => UserStatus::InGame,
+            "online" => UserStatus::Online,
+            "offline" => UserStatus::Offline,
+            "invisible" => UserStatus::Invisible,
+            _ => UserStatus::Unknown(key),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct User {
+    pub ingame_name: CompactStr,
+    pub status: UserStatus,
+    pub reputation: i32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Order {
+    pub platinum: i32,
+    pub quantity: i32,
+    pub order_type: OrderType,
+    pub region: CompactStr,
+    pub visible: bool,
+    pub user: User,
+    pub mod_rank: Option<i32>,
+}
+
+/// wm订单的过滤条件, 可以按群设置默认值.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct OrderFilter {
+    /// 包括在线但不在游戏中的玩家
+    pub online: bool,
+    /// 为空时不限区域
+    pub region: Option<CompactStr>,
+    pub min_reputation: i32,
+    pub min_quantity: i32,
+    /// 显示的订单条数
+    pub count: usize,
+}
+
+impl Default for OrderFilter {
+    fn default() -> Self {
+        OrderFilter {
+            online: false,
+            region: Some(CompactStr::new_inline("en")),
+            min_reputation: 0,
+            min_quantity: 0,
+            count: 4,
+        }
+    }
+}
+
+impl OrderFilter {
+    pub fn matches(&self, order: &Order) -> bool {
+        let status = match order.user.status {
+            UserStatus::InGame => true,
+            UserStatus::Online => self.online,
+            _ => false,
+        };
+        status
+            && order.visible
+            && self
+                .region
+                .as_ref()
+                .map_or(true, |region| order.region == *region)
+            && order.user.reputation >= self.min_reputation
+            && order.quantity >= self.min_quantity
+    }
+}
+
+/// 符合`filter`的`order_type`订单, `mod_rank`不为空时只看对应等级(没有等级的物品不受影响).
+pub fn matching_orders<'a: 'f, 'f>(
+    orders: &'a [Order],
+    order_type: &'f OrderType,
+    mod_rank: Option<i32>,
+    filter: &'f OrderFilter,
+) -> impl Iterator<Item = &'a Order> + 'f {
+    orders
+        .iter()
+        .filter(move |order| order.order_type == *order_type)
+        .filter(move |order| filter.matches(order))
+        .filter(move |order| match (mod_rank, order.mod_rank) {
+            (Some(lvl), Some(lvl2)) => lvl == lvl2,
+            _ => true,
+        })
+}
+
+/// 游戏中卖家里价格最低的订单
+pub fn lowest_ingame_sell(orders: &[Order], mod_rank: Option<i32>) -> Option<&Order> {
+    let filter = OrderFilter::default();
+    matching_orders(orders, &OrderType::Sell, mod_rank, &filter).min_by_key(|order| order.platinum)
+}
+
+impl WarframeApi {
+    pub async fn wm_item(&self, platform: Platform, url_name: &str) -> WfApiResult<Vec<Order>> {
+        #[derive(Deserialize, Debug, Clone)]
+        struct Body {
+            payload: Payload,
+        }
+
+        #[derive(Deserialize, Debug, Clone)]
+        struct Payload {
+            orders: Vec<Order>,
+        }
+
+        let path = format!("/items/{}/orders", url_name);
+        let payload = self
+            .cached_json::<Body, _>(
+                &format!("wm:{}:{}", platform.market(), path),
+                |_| ORDERS_TTL,
+                self.wm_platform_get(platform, &path),
+            )
+            .await?
+            .payload;
+
+        Ok(payload.orders)
+    }
+}
+
+/// `update_rivens_db`的结果
+#[derive(Debug, Clone)]
+pub struct RivensDbUpdate {
+    pub rivens: u32,
+    pub attributes: u32,
+    /// 有武器增减时的新版本
+    pub version: Option<CatalogVersion>,
+}
+
+impl WarframeApi {
+    /// 替换紫卡武器名到`url_name`的映射, 储存武器的紫卡类型和分类, 并更新紫卡词条, 见`update_riven_attributes`.
+    pub async fn update_rivens_db(&self, db: Arc<sled::Db>) -> anyhow::Result<RivensDbUpdate> {
+        #[derive(Deserialize, Debug, Clone)]
+        struct Body {
+            payload: Payload,
+        }
+
+        #[derive(Deserialize, Debug, Clone)]
+        struct Payload {
+            items: Vec<Item>,
+        }
+
+        #[derive(Deserialize, Debug, Clone)]
+        struct Item {
+            url_name: CompactStr,
+            item_name: CompactStr,
+            riven_type: Option<CompactStr>,
+            group: Option<CompactStr>,
+        }
+
+        let _guard = UPDATE_LOCK.lock().await;
+
+        let payload = self
+            .send_json::<Body>(self.wm_get("/riven/items").header("Language", "zh-hans"))
+            .await?
+            .payload;
+
+        let weapons = db.open_tree(RIVEN_WEAPONS_TREE)?;
+        weapons.clear()?;
+        for item in &payload.items {
+            let weapon = RivenWeapon {
+                item_name: item.item_name.clone(),
+                riven_type: item.riven_type.clone(),
+                group: item.group.clone(),
+            };
+            weapons.insert(item.url_name.as_bytes(), serde_json::to_vec(&weapon)?)?;
+        }
+
+        let items = payload
+            .items
+            .into_iter()
+            .map(|item| (item.item_name, item.url_name))
+            .collect::<Vec<_>>();
+        let version = replace_catalog(&db, &items)?;
+
+        db.flush_async().await?;
+
+        let attributes = self.update_riven_attributes(db).await?;
+
+        Ok(RivensDbUpdate {
+            rivens: items.len() as u32,
+            attributes,
+            version,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Auction {
+    pub buyout_price: Option<i32>,
+    pub starting_price: i32,
+    pub private: bool,
+    pub visible: bool,
+    pub item: AuctionItem,
+    pub closed: bool,
+    pub is_direct_sell: bool,
+    pub owner: User,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AuctionItem {
+    pub name: CompactStr,
+    pub mastery_level: i32,
+    pub mod_rank: i32,
+    pub polarity: Polarity,
+    pub re_rolls: i32,
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Attribute {
+    pub positive: bool,
+    pub value: f64,
+    pub url_name: CompactStr,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "CompactStr")]
+pub enum Polarity {
+    Madurai,
+    Vazarin,
+    Naramon,
+    Zenurik,
+    Unairu,
+    Penjaga,
+    Umbra,
+    Any,
+    Unknown(CompactStr),
+}
+
+impl Polarity {
+    pub fn nickname(&self) -> &str {
+        match self {
+            Polarity::Madurai => "r",
+            Polarity::Vazarin => "盾",
+            Polarity::Naramon => "-",
+            Polarity::Zenurik => "=",
+            Polarity::Unairu => "u",
+            Polarity::Penjaga => "p",
+            Polarity::Umbra => "Ω",
+            Polarity::Any => "任意",
+            Polarity::Unknown(key) => key,
+        }
+    }
+}
+
+impl Polarity {
+    /// `nickname`或接口中的名字(不区分大小写)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match Polarity::from(CompactStr::new(name.to_ascii_lowercase())) {
+            Polarity::Unknown(_) => [
+                Polarity::Madurai,
+                Polarity::Vazarin,
+                Polarity::Naramon,
+                Polarity::Zenurik,
+                Polarity::Unairu,
+                Polarity::Penjaga,
+                Polarity::Umbra,
+                Polarity::Any,
+            ]
+            .into_iter()
+            .find(|polarity| polarity.nickname().eq_ignore_ascii_case(name)),
+            polarity => Some(polarity),
+        }
+    }
+
+    /// warframe.market接口中的名字
+    pub fn key(&self) -> &str {
+        match self {
+            Polarity::Madurai => "madurai",
+            Polarity::Vazarin => "vazarin",
+            Polarity::Naramon => "naramon",
+            Polarity::Zenurik => "zenurik",
+            Polarity::Unairu => "unairu",
+            Polarity::Penjaga => "penjaga",
+            Polarity::Umbra => "umbra",
+            Polarity::Any => "any",
+            Polarity::Unknown(key) => key,
+        }
+    }
+}
+
+impl From<CompactStr> for Polarity {
+    fn from(key: CompactStr) -> Self {
+        match key.as_str() {
+            "madurai" => Polarity::Madurai,
+            "vazarin" => Polarity::Vazarin,
+            "naramon" => Polarity::Naramon,
+            "zenurik" => Polarity::Zenurik,
+            "unairu" => Polarity::Unairu,
+            "penjaga" => Polarity::Penjaga,
+            "umbra" => Polarity::Umbra,
+            "any" => Polarity::Any,
+            _ => Polarity::Unknown(key),
+        }
+    }
+}
+
+/// 紫卡拍卖的出售方式
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SaleType {
+    /// 有一口价的
+    Buyout,
+    /// 可以竞拍的(不是直售)
+    Auction,
+    /// 只能按一口价直接购买的
+    Direct,
+}
+
+/// 紫卡拍卖的搜索条件, 大部分作为`auctions/search`的参数, 卖家声望和竞拍在本地过滤.
+#[derive(Debug, Clone, Default)]
+pub struct RivenQuery {
+    pub weapon_url_name: CompactStr,
+    pub positive_stats: Vec<CompactStr>,
+    pub negative_stats: Option<CompactStr>,
+    pub mastery_rank_min: Option<i32>,
+    pub mastery_rank_max: Option<i32>,
+    pub re_rolls_min: Option<i32>,
+    pub re_rolls_max: Option<i32>,
+    pub polarity: Option<Polarity>,
+    /// 只看满级的
+    pub maxed: bool,
+    pub sale_type: Option<SaleType>,
+    pub min_reputation: i32,
+}
+
+impl RivenQuery {
+    fn path(&self) -> String {
+        let mut path = format!(
+            "/auctions/search?type=riven&weapon_url_name={}&sort_by=price_asc",
+            self.weapon_url_name
+        );
+        if !self.positive_stats.is_empty() {
+            path.push_str(&format!(
+                "&positive_stats={}",
+                self.positive_stats.iter().join(",")
+            ));
+        }
+        let params = [
+            (
+                "negative_stats",
+                self.negative_stats.as_ref().map(|s| s.to_string()),
+            ),
+            (
+                "mastery_rank_min",
+                self.mastery_rank_min.map(|n| n.to_string()),
+            ),
+            (
+                "mastery_rank_max",
+                self.mastery_rank_max.map(|n| n.to_string()),
+            ),
+            ("re_rolls_min", self.re_rolls_min.map(|n| n.to_string())),
+            ("re_rolls_max", self.re_rolls_max.map(|n| n.to_string())),
+            (
+                "polarity",
+                self.polarity.as_ref().map(|p| p.key().to_owned()),
+            ),
+            ("mod_rank", self.maxed.then(|| "maxed".to_owned())),
+            (
+                "buyout_policy",
+                match self.sale_type {
+                    Some(SaleType::Buyout) => Some("with".to_owned()),
+                    Some(SaleType::Direct) => Some("direct".to_owned()),
+                    _ => None,
+                },
+            ),
+        ];
+        params.iter().for_each(|(name, value)| {
+            if let Some(value) = value {
+                path.push_str(&format!("&{}={}", name, value));
+            }
+        });
+        path
+    }
+
+    /// 接口参数不支持的条件
+    fn matches(&self, auction: &Auction) -> bool {
+        auction.owner.reputation >= self.min_reputation
+            && match self.sale_type {
+                Some(SaleType::Auction) => !auction.is_direct_sell,
+                Some(SaleType::Direct) => auction.is_direct_sell,
+                Some(SaleType::Buyout) => auction.buyout_price.is_some(),
+                None => true,
+            }
+    }
+}
+
+impl WarframeApi {
+    pub async fn wm_riven(
+        &self,
+        platform: Platform,
+        query: &RivenQuery,
+    ) -> WfApiResult<Vec<Auction>> {
+        #[derive(Deserialize, Debug, Clone)]
+        struct Body {
+            payload: Payload,
+        }
+
+        #[derive(Deserialize, Debug, Clone)]
+        struct Payload {
+            auctions: Vec<Auction>,
+        }
+
+        let path = query.path();
+        let payload = self
+            .cached_json::<Body, _>(
+                &format!("wm:{}:{}", platform.market(), path),
+                |_| ORDERS_TTL,
+                self.wm_platform_get(platform, &path),
+            )
+            .await?
+            .payload;
+
+        Ok(payload
+            .auctions
+            .into_iter()
+            .filter(|auction| query.matches(auction))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_unknown_polarity() {
+        let items: Vec<AuctionItem> = serde_json::from_str(
+            r#"[
+                {"name": "a", "mastery_level": 8, "mod_rank": 0, "polarity": "zenurik", "re_rolls": 0, "attributes": []},
+                {"name": "b", "mastery_level": 8, "mod_rank": 0, "polarity": "foo", "re_rolls": 0, "attributes": []}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(items[0].polarity, Polarity::Zenurik);
+        assert_eq!(items[0].polarity.nickname(), "=");
+        assert_eq!(items[1].polarity, Polarity::Unknown("foo".into()));
+        assert_eq!(items[1].polarity.nickname(), "foo");
+        assert_eq!(items[1].polarity.key(), "foo");
+    }
+
+    #[test]
+    fn riven_query_path() {
+        let query = RivenQuery {
+            weapon_url_name: "rubico".into(),
+            positive_stats: vec!["critical_damage".into(), "multishot".into()],
+            negative_stats: Some("none".into()),
+            mastery_rank_min: Some(8),
+            mastery_rank_max: Some(12),
+            polarity: Some(Polarity::Madurai),
+            maxed: true,
+            sale_type: Some(SaleType::Buyout),
+            ..Default::default()
+        };
+        assert_eq!(
+            query.path(),
+            "/auctions/search?type=riven&weapon_url_name=rubico&sort_by=price_asc\
+             &positive_stats=critical_damage,multishot&negative_stats=none\
+             &mastery_rank_min=8&mastery_rank_max=12&polarity=madurai&mod_rank=maxed&buyout_policy=with"
+        );
+
+        let query = RivenQuery {
+            weapon_url_name: "rubico".into(),
+            sale_type: Some(SaleType::Auction),
+            ..Default::default()
+        };
+        assert_eq!(
+            query.path(),
+            "/auctions/search?type=riven&weapon_url_name=rubico&sort_by=price_asc"
+        );
+    }
+
+    #[test]
+    fn decode_unknown_enemy() {
+        let enemies: Vec<Enemy> = serde_json::from_str(r#"["Murmur", "NewFaction"]"#).unwrap();
+        assert_eq!(enemies[0], Enemy::Murmur);
+        assert_eq!(enemies[0].nickname(), "低语者");
+        assert_eq!(enemies[1], Enemy::Unknown("NewFaction".into()));
+        assert_eq!(enemies[1].nickname(), "NewFaction");
+        assert_eq!(CompactStr::from(enemies[1].clone()), "NewFaction");
+    }
+
+    #[test]
+    fn decode_unknown_order_fields() {
+        let orders: Vec<Order> = serde_json::from_str(
+            r#"[{
+                "platinum": 30,
+                "quantity": 1,
+                "order_type": "trade",
+                "region": "en",
+                "visible": true,
+                "user": {"ingame_name": "x", "status": "afk", "reputation": 0},
+                "mod_rank": null
+            }]"#,
+        )
+        .unwrap();
+        assert_eq!(orders[0].order_type, OrderType::Unknown("trade".into()));
+        assert_eq!(orders[0].user.status, UserStatus::Unknown("afk".into()));
+        assert_eq!(orders[0].user.status.chinese(), "afk");
+    }
+}
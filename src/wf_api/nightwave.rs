@@ -0,0 +1,152 @@
+use compact_str::CompactStr;
+use proc_qq::re_exports::ricq::msg::MessageChain;
+use proc_qq::MessageChainParseTrait;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::wf_api::{cache, remaining_time, ArchonHunt, Platform, WarframeApi, WfApiResult};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NightwaveChallenge {
+    pub id: CompactStr,
+    #[serde(with = "time::serde::iso8601")]
+    pub expiry: OffsetDateTime,
+
+    pub title: CompactStr,
+    pub desc: CompactStr,
+    pub reputation: i32,
+    #[serde(rename = "isDaily", default)]
+    pub is_daily: bool,
+    #[serde(rename = "isElite", default)]
+    pub is_elite: bool,
+}
+
+impl NightwaveChallenge {
+    pub fn kind(&self) -> &'static str {
+        match (self.is_daily, self.is_elite) {
+            (true, _) => "每日",
+            (_, true) => "精英每周",
+            _ => "每周",
+        }
+    }
+}
+
+/// 两个赛季之间接口会返回空对象, 所以字段都有默认值.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Nightwave {
+    #[serde(default)]
+    pub id: CompactStr,
+    pub season: Option<i32>,
+    #[serde(rename = "activeChallenges", default)]
+    pub active_challenges: Vec<NightwaveChallenge>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SteelPathReward {
+    pub name: CompactStr,
+    pub cost: i32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SteelPath {
+    #[serde(rename = "currentReward")]
+    pub current_reward: SteelPathReward,
+    #[serde(with = "time::serde::iso8601")]
+    pub expiry: OffsetDateTime,
+}
+
+impl WarframeApi {
+    pub async fn nightwave(&self, platform: Platform) -> WfApiResult<Nightwave> {
+        let path = "/nightwave?language=zh";
+        self.cached_json(
+            &format!("api:{}{}", platform.worldstate(), path),
+            |n: &Nightwave| cache::until_earliest(n.active_challenges.iter().map(|c| c.expiry)),
+            self.api_get(platform, path),
+        )
+        .await
+    }
+
+    pub async fn steel_path(&self, platform: Platform) -> WfApiResult<SteelPath> {
+        let path = "/steelPath?language=zh";
+        self.cached_json(
+            &format!("api:{}{}", platform.worldstate(), path),
+            |s: &SteelPath| cache::until_expiry(s.expiry),
+            self.api_get(platform, path),
+        )
+        .await
+    }
+}
+
+pub fn gen_nightwave_info(data: &Nightwave) -> MessageChain {
+    if data.active_challenges.is_empty() {
+        return "现在没有午夜电波任务".parse_message_chain();
+    }
+
+    let mut info = match data.season {
+        Some(season) => format!("午夜电波 第{}季", season),
+        None => "午夜电波".to_owned(),
+    };
+    let mut challenges = data.active_challenges.iter().collect::<Vec<_>>();
+    challenges.sort_by_key(|c| (!c.is_daily, c.is_elite, c.expiry));
+    challenges.into_iter().for_each(|c| {
+        info.push_str(&format!(
+            "\n[{kind}] {title} ({reputation}声望, 剩余 {time})\n   {desc}",
+            kind = c.kind(),
+            title = c.title,
+            reputation = c.reputation,
+            time = remaining_time(c.expiry),
+            desc = c.desc,
+        ));
+    });
+    info.parse_message_chain()
+}
+
+pub fn gen_steel_path_info(data: &SteelPath) -> String {
+    format!(
+        "钢铁之路 Teshin 本周奖励: {} ({}钢铁精华)",
+        data.current_reward.name, data.current_reward.cost
+    )
+}
+
+/// 周一重置的汇总, 获取失败的部分为`None`.
+pub fn gen_weekly_digest(
+    nightwave: Option<&Nightwave>,
+    archon_hunt: Option<&ArchonHunt>,
+    steel_path: Option<&SteelPath>,
+) -> MessageChain {
+    let mut info = String::from("每周重置了!");
+
+    if let Some(archon) = archon_hunt {
+        info.push_str(&format!(
+            "\n执刑官: {} ({}源力石)",
+            archon.boss,
+            archon.shard()
+        ));
+        archon.missions.iter().for_each(|mission| {
+            info.push_str(&format!("\n   {} - {}", mission.r#type, mission.node));
+        });
+    }
+
+    if let Some(steel) = steel_path {
+        info.push('\n');
+        info.push_str(&gen_steel_path_info(steel));
+    }
+
+    if let Some(nightwave) = nightwave {
+        nightwave
+            .active_challenges
+            .iter()
+            .filter(|c| !c.is_daily)
+            .for_each(|c| {
+                info.push_str(&format!(
+                    "\n[{}] {} ({}声望): {}",
+                    c.kind(),
+                    c.title,
+                    c.reputation,
+                    c.desc
+                ));
+            });
+    }
+
+    info.parse_message_chain()
+}
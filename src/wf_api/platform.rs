@@ -0,0 +1,73 @@
+/// 游戏平台, warframestat.us和warframe.market对同一平台的叫法不同.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Platform {
+    Pc,
+    Ps4,
+    Xbox,
+    Switch,
+}
+
+impl Platform {
+    pub const ALL: &'static [Platform] = &[
+        Platform::Pc,
+        Platform::Ps4,
+        Platform::Xbox,
+        Platform::Switch,
+    ];
+
+    /// warframestat.us路径中的平台名
+    pub fn worldstate(&self) -> &'static str {
+        match self {
+            Platform::Pc => "pc",
+            Platform::Ps4 => "ps4",
+            Platform::Xbox => "xb1",
+            Platform::Switch => "swi",
+        }
+    }
+
+    /// warframe.market的`Platform` header
+    pub fn market(&self) -> &'static str {
+        match self {
+            Platform::Pc => "pc",
+            Platform::Ps4 => "ps4",
+            Platform::Xbox => "xbox",
+            Platform::Switch => "switch",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Platform::Pc => "PC",
+            Platform::Ps4 => "PS",
+            Platform::Xbox => "Xbox",
+            Platform::Switch => "Switch",
+        }
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Platform::Pc => &["pc", "电脑"],
+            Platform::Ps4 => &["ps4", "ps5", "ps", "psn", "playstation"],
+            Platform::Xbox => &["xbox", "xb1", "xb", "xsx"],
+            Platform::Switch => &["switch", "swi", "ns"],
+        }
+    }
+
+    pub fn from_alias(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|p| p.aliases().contains(&name.as_str()))
+    }
+
+    /// 参数的第一项是平台名时返回平台和剩下的参数, 如`ps4 赤毒`.
+    pub fn split_prefix(param: &str) -> (Option<Self>, &str) {
+        let param = param.trim();
+        let (first, rest) = param.split_once(' ').unwrap_or((param, ""));
+        match Self::from_alias(first) {
+            Some(platform) => (Some(platform), rest.trim()),
+            None => (None, param),
+        }
+    }
+}
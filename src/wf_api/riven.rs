@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use compact_str::CompactStr;
+use serde::{Deserialize, Serialize};
+
+use crate::wf_api::{WarframeApi, RIVEN_ATTR};
+
+/// 紫卡数据库中的tree, key为武器的`url_name`, value为json格式的`RivenWeapon`.
+pub const RIVEN_WEAPONS_TREE: &str = "riven_weapons";
+/// 紫卡数据库中的tree, key为词条的`url_name`, value为json格式的`RivenAttribute`.
+pub const RIVEN_ATTRS_TREE: &str = "riven_attrs";
+/// 紫卡数据库中的tree, key为`riven_attr_key`处理过的词条名/简称/别名, value为词条的`url_name`.
+pub const RIVEN_ATTR_NAMES_TREE: &str = "riven_attr_names";
+/// 紫卡数据库中的tree, 用户添加的词条别名, 格式同`RIVEN_ATTR_NAMES_TREE`, 更新词条时会重新加入索引.
+pub const RIVEN_ATTR_ALIASES_TREE: &str = "riven_attr_aliases";
+
+/// 可以有紫卡的武器
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RivenWeapon {
+    pub item_name: CompactStr,
+    /// 紫卡类型, 如`rifle`, `melee`
+    pub riven_type: Option<CompactStr>,
+    /// 武器分类, 如`primary`, `sentinel`
+    pub group: Option<CompactStr>,
+}
+
+/// 紫卡词条
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RivenAttribute {
+    pub url_name: CompactStr,
+    /// 中文名
+    pub effect: CompactStr,
+    /// 只会出现在这些类型的紫卡上, 为空时不限
+    #[serde(default)]
+    pub exclusive_to: Option<Vec<CompactStr>>,
+    #[serde(default)]
+    pub positive_only: bool,
+    #[serde(default)]
+    pub negative_only: bool,
+}
+
+impl RivenAttribute {
+    /// 能否出现在`riven_type`类型的紫卡上
+    pub fn applies_to(&self, riven_type: &str) -> bool {
+        self.exclusive_to
+            .as_ref()
+            .map_or(true, |types| types.iter().any(|t| t.as_str() == riven_type))
+    }
+}
+
+/// 紫卡类型的中文名
+pub fn riven_type_name(riven_type: &str) -> &str {
+    match riven_type {
+        "rifle" => "步枪",
+        "shotgun" => "霰弹枪",
+        "pistol" => "手枪",
+        "melee" => "近战",
+        "zaw" => "自制近战",
+        "kitgun" => "组合枪",
+        "archgun" => "空战枪",
+        other => other,
+    }
+}
+
+/// 词条名索引的key, 不区分大小写和空格.
+pub fn riven_attr_key(name: &str) -> String {
+    name.to_ascii_lowercase().replace(' ', "")
+}
+
+impl WarframeApi {
+    /// 储存所有紫卡词条, 并重建词条名索引(中文名, `url_name`, 内置简称和用户别名). 返回储存的词条数.
+    pub async fn update_riven_attributes(&self, db: Arc<sled::Db>) -> anyhow::Result<u32> {
+        #[derive(Deserialize, Debug, Clone)]
+        struct Body {
+            payload: Payload,
+        }
+
+        #[derive(Deserialize, Debug, Clone)]
+        struct Payload {
+            attributes: Vec<RivenAttribute>,
+        }
+
+        let payload = self
+            .send_json::<Body>(
+                self.wm_get("/riven/attributes")
+                    .header("Language", "zh-hans"),
+            )
+            .await?
+            .payload;
+
+        let attrs = db.open_tree(RIVEN_ATTRS_TREE)?;
+        let names = db.open_tree(RIVEN_ATTR_NAMES_TREE)?;
+        names.clear()?;
+
+        let mut num = 0u32;
+        for attr in &payload.attributes {
+            attrs.insert(attr.url_name.as_bytes(), serde_json::to_vec(attr)?)?;
+            names.insert(riven_attr_key(&attr.effect), attr.url_name.as_bytes())?;
+            names.insert(riven_attr_key(&attr.url_name), attr.url_name.as_bytes())?;
+            num += 1;
+        }
+        for (name, url_name) in RIVEN_ATTR.entries() {
+            names.insert(riven_attr_key(name), url_name.as_bytes())?;
+        }
+        for alias in db.open_tree(RIVEN_ATTR_ALIASES_TREE)?.iter() {
+            let (name, url_name) = alias?;
+            names.insert(name, url_name)?;
+        }
+
+        db.flush_async().await?;
+
+        Ok(num)
+    }
+}
@@ -0,0 +1,124 @@
+use compact_str::CompactStr;
+use proc_qq::re_exports::ricq::msg::MessageChain;
+use proc_qq::MessageChainParseTrait;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::wf_api::{cache, remaining_time, Platform, WarframeApi, WfApiResult};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SortieVariant {
+    pub node: CompactStr,
+    #[serde(rename = "missionType")]
+    pub mission_type: CompactStr,
+    pub modifier: CompactStr,
+    #[serde(rename = "modifierDescription")]
+    pub modifier_description: Option<CompactStr>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Sortie {
+    pub id: CompactStr,
+    #[serde(with = "time::serde::iso8601")]
+    pub activation: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub expiry: OffsetDateTime,
+
+    pub boss: CompactStr,
+    pub faction: CompactStr,
+    pub variants: Vec<SortieVariant>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ArchonMission {
+    pub node: CompactStr,
+    #[serde(rename = "type")]
+    pub r#type: CompactStr,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ArchonHunt {
+    pub id: CompactStr,
+    #[serde(with = "time::serde::iso8601")]
+    pub activation: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub expiry: OffsetDateTime,
+
+    pub boss: CompactStr,
+    pub faction: CompactStr,
+    pub missions: Vec<ArchonMission>,
+}
+
+impl ArchonHunt {
+    /// 执刑官掉落的源力石颜色
+    pub fn shard(&self) -> &'static str {
+        if self.boss.contains("Amar") || self.boss.contains("阿玛") {
+            "深红"
+        } else if self.boss.contains("Nira") || self.boss.contains("妮拉") {
+            "琥珀"
+        } else if self.boss.contains("Boreal") || self.boss.contains("波里") {
+            "蔚蓝"
+        } else {
+            "未知"
+        }
+    }
+}
+
+impl WarframeApi {
+    pub async fn sortie(&self, platform: Platform) -> WfApiResult<Sortie> {
+        let path = "/sortie?language=zh";
+        self.cached_json(
+            &format!("api:{}{}", platform.worldstate(), path),
+            |s: &Sortie| cache::until_expiry(s.expiry),
+            self.api_get(platform, path),
+        )
+        .await
+    }
+
+    pub async fn archon_hunt(&self, platform: Platform) -> WfApiResult<ArchonHunt> {
+        let path = "/archonHunt?language=zh";
+        self.cached_json(
+            &format!("api:{}{}", platform.worldstate(), path),
+            |a: &ArchonHunt| cache::until_expiry(a.expiry),
+            self.api_get(platform, path),
+        )
+        .await
+    }
+}
+
+pub fn gen_sortie_info(data: &Sortie) -> MessageChain {
+    let mut info = format!(
+        "boss: {boss} \n阵营: {faction} \n剩余时间(约): {time}",
+        boss = data.boss,
+        faction = data.faction,
+        time = remaining_time(data.expiry),
+    );
+    data.variants.iter().enumerate().for_each(|(i, variant)| {
+        info.push_str(&format!(
+            "\n{}. {} - {} \n   {}",
+            i + 1,
+            variant.mission_type,
+            variant.node,
+            variant.modifier,
+        ));
+    });
+    info.parse_message_chain()
+}
+
+pub fn gen_archon_hunt_info(data: &ArchonHunt) -> MessageChain {
+    let mut info = format!(
+        "执刑官: {boss} ({shard}源力石) \n剩余时间(约): {time}",
+        boss = data.boss,
+        shard = data.shard(),
+        time = remaining_time(data.expiry),
+    );
+    data.missions.iter().enumerate().for_each(|(i, mission)| {
+        info.push_str(&format!(
+            "\n{}. {} - {}",
+            i + 1,
+            mission.r#type,
+            mission.node
+        ));
+    });
+    info.parse_message_chain()
+}
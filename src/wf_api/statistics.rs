@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use itertools::Itertools;
+use proc_qq::re_exports::ricq::msg::MessageChain;
+use proc_qq::MessageChainParseTrait;
+use serde::Deserialize;
+
+use crate::wf_api::{Platform, WarframeApi, WfApiResult};
+
+/// 成交统计每小时才更新一次
+const STATISTICS_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// 一个时间段(48小时统计中为一小时, 90天统计中为一天)的成交统计
+#[derive(Deserialize, Debug, Clone)]
+pub struct Statistic {
+    pub volume: i32,
+    pub min_price: f64,
+    pub max_price: f64,
+    pub avg_price: f64,
+    pub median: f64,
+    pub mod_rank: Option<i32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Statistics {
+    #[serde(rename = "48hours", default)]
+    pub hours_48: Vec<Statistic>,
+    #[serde(rename = "90days", default)]
+    pub days_90: Vec<Statistic>,
+}
+
+/// 同一mod等级在整个统计范围内的汇总
+#[derive(Debug, Clone)]
+pub struct StatisticSummary {
+    pub mod_rank: Option<i32>,
+    pub volume: i32,
+    pub min: f64,
+    pub max: f64,
+    /// 按成交量加权的均价
+    pub avg: f64,
+    /// 各时间段中位数的中位数
+    pub median: f64,
+}
+
+/// 按mod等级汇总, 等级从低到高.
+pub fn summarize_statistics(data: &[Statistic]) -> Vec<StatisticSummary> {
+    data.iter()
+        .into_group_map_by(|s| s.mod_rank)
+        .into_iter()
+        .sorted_by_key(|(mod_rank, _)| *mod_rank)
+        .map(|(mod_rank, stats)| {
+            let volume = stats.iter().map(|s| s.volume).sum::<i32>();
+            let avg = if volume > 0 {
+                stats
+                    .iter()
+                    .map(|s| s.avg_price * s.volume as f64)
+                    .sum::<f64>()
+                    / volume as f64
+            } else {
+                stats.iter().map(|s| s.avg_price).sum::<f64>() / stats.len() as f64
+            };
+            let medians = stats
+                .iter()
+                .map(|s| s.median)
+                .sorted_by(|l, r| l.total_cmp(r))
+                .collect::<Vec<_>>();
+
+            StatisticSummary {
+                mod_rank,
+                volume,
+                min: stats.iter().map(|s| s.min_price).fold(f64::MAX, f64::min),
+                max: stats.iter().map(|s| s.max_price).fold(f64::MIN, f64::max),
+                avg,
+                median: medians[medians.len() / 2],
+            }
+        })
+        .collect()
+}
+
+impl WarframeApi {
+    /// 物品已完成交易的统计
+    pub async fn wm_statistics(
+        &self,
+        platform: Platform,
+        url_name: &str,
+    ) -> WfApiResult<Statistics> {
+        #[derive(Deserialize, Debug, Clone)]
+        struct Body {
+            payload: Payload,
+        }
+
+        #[derive(Deserialize, Debug, Clone)]
+        struct Payload {
+            statistics_closed: Statistics,
+        }
+
+        let path = format!("/items/{}/statistics", url_name);
+        let payload = self
+            .cached_json::<Body, _>(
+                &format!("wm:{}:{}", platform.market(), path),
+                |_| STATISTICS_TTL,
+                self.wm_platform_get(platform, &path),
+            )
+            .await?
+            .payload;
+
+        Ok(payload.statistics_closed)
+    }
+}
+
+fn gen_summary_lines(title: &str, data: &[Statistic], info: &mut String) {
+    info.push_str(&format!("\n== {} ==", title));
+    let summaries = summarize_statistics(data);
+    if summaries.is_empty() {
+        info.push_str("\n没有成交");
+    }
+    summaries.iter().for_each(|s| {
+        info.push('\n');
+        if let Some(rank) = s.mod_rank {
+            info.push_str(&format!("[{} 级] ", rank));
+        }
+        info.push_str(&format!(
+            "成交 {} 个, 最低 {}, 最高 {}, 均价 {:.1}, 中位数 {}",
+            s.volume, s.min, s.max, s.avg, s.median
+        ));
+    });
+}
+
+pub fn gen_statistics_info(item_name: &str, data: &Statistics) -> MessageChain {
+    let mut info = format!("{} 的成交统计", item_name);
+    gen_summary_lines("48小时", &data.hours_48, &mut info);
+    gen_summary_lines("90天", &data.days_90, &mut info);
+    info.parse_message_chain()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(volume: i32, avg_price: f64, median: f64, mod_rank: Option<i32>) -> Statistic {
+        Statistic {
+            volume,
+            min_price: avg_price - 1.0,
+            max_price: avg_price + 1.0,
+            avg_price,
+            median,
+            mod_rank,
+        }
+    }
+
+    #[test]
+    fn summarize_by_mod_rank() {
+        let summaries = summarize_statistics(&[
+            stat(3, 10.0, 10.0, Some(10)),
+            stat(1, 20.0, 30.0, Some(10)),
+            stat(2, 5.0, 5.0, Some(0)),
+            stat(1, 40.0, 20.0, Some(10)),
+        ]);
+        assert_eq!(summaries.len(), 2);
+
+        let rank0 = &summaries[0];
+        assert_eq!(rank0.mod_rank, Some(0));
+        assert_eq!(rank0.volume, 2);
+
+        let rank10 = &summaries[1];
+        assert_eq!(rank10.mod_rank, Some(10));
+        assert_eq!(rank10.volume, 5);
+        assert_eq!(rank10.min, 9.0);
+        assert_eq!(rank10.max, 41.0);
+        assert_eq!(rank10.avg, (30.0 + 20.0 + 40.0) / 5.0);
+        assert_eq!(rank10.median, 20.0);
+    }
+
+    #[test]
+    fn summarize_without_volume() {
+        let summaries =
+            summarize_statistics(&[stat(0, 10.0, 10.0, None), stat(0, 20.0, 20.0, None)]);
+        assert_eq!(summaries[0].avg, 15.0);
+    }
+}
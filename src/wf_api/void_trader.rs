@@ -0,0 +1,115 @@
+use compact_str::CompactStr;
+use itertools::Itertools;
+use proc_qq::re_exports::ricq::msg::MessageChain;
+use proc_qq::MessageChainParseTrait;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::wf_api::{
+    cache, lowest_ingame_sell, remaining_time, Platform, WarframeApi, WfApiError, WfApiResult,
+};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct VoidTraderItem {
+    pub item: CompactStr,
+    pub ducats: Option<i32>,
+    pub credits: Option<i32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct VoidTrader {
+    pub id: CompactStr,
+    #[serde(with = "time::serde::iso8601")]
+    pub activation: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub expiry: OffsetDateTime,
+
+    pub character: CompactStr,
+    pub location: CompactStr,
+    pub active: bool,
+    #[serde(default)]
+    pub inventory: Vec<VoidTraderItem>,
+}
+
+impl WarframeApi {
+    pub async fn void_trader(&self, platform: Platform) -> WfApiResult<VoidTrader> {
+        let path = "/voidTrader?language=zh";
+        self.cached_json(
+            &format!("api:{}{}", platform.worldstate(), path),
+            |v: &VoidTrader| {
+                // 没来的时候缓存到他到达为止
+                cache::until_expiry(if v.active { v.expiry } else { v.activation })
+            },
+            self.api_get(platform, path),
+        )
+        .await
+    }
+
+    /// 查询奸商每件商品在wm上游戏中卖家的最低价, 结果与`inventory`一一对应.
+    ///
+    /// `url_name`负责把商品名转换为wm的`url_name`, 找不到的商品不查询.
+    /// 找不到, 不能交易(wm上404)或者查询出错的商品价格为`None`.
+    pub async fn void_trader_prices(
+        &self,
+        platform: Platform,
+        inventory: &[VoidTraderItem],
+        url_name: impl Fn(&str) -> Option<String>,
+    ) -> Vec<Option<i32>> {
+        let mut prices = Vec::with_capacity(inventory.len());
+        for item in inventory {
+            let price = match url_name(&item.item) {
+                None => None,
+                Some(url_name) => match self.wm_item(platform, &url_name).await {
+                    Ok(orders) => lowest_ingame_sell(&orders, None).map(|order| order.platinum),
+                    Err(WfApiError::NotFound) => None,
+                    Err(err) => {
+                        tracing::warn!("void trader item {} price error: {}", url_name, err);
+                        None
+                    }
+                },
+            };
+            prices.push(price);
+        }
+        prices
+    }
+}
+
+/// `prices`为`void_trader_prices`的结果, 有价格时按价格从高到低排列商品.
+pub fn gen_void_trader_info(data: &VoidTrader, prices: Option<&[Option<i32>]>) -> MessageChain {
+    if !data.active {
+        return format!(
+            "{name} 将在 {time} 后到达 {location}",
+            name = data.character,
+            time = remaining_time(data.activation),
+            location = data.location,
+        )
+        .parse_message_chain();
+    }
+
+    let mut info = format!(
+        "{name} 正在 {location}, 还有 {time} 离开",
+        name = data.character,
+        location = data.location,
+        time = remaining_time(data.expiry),
+    );
+
+    data.inventory
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (item, prices.and_then(|p| p.get(i).copied().flatten())))
+        .sorted_by(|(_, l), (_, r)| r.cmp(l))
+        .for_each(|(item, price)| {
+            info.push_str(&format!("\n{}", item.item));
+            if let Some(ducats) = item.ducats.filter(|d| *d > 0) {
+                info.push_str(&format!(" {}杜卡特", ducats));
+            }
+            if let Some(credits) = item.credits.filter(|c| *c > 0) {
+                info.push_str(&format!(" {}现金", credits));
+            }
+            if let Some(price) = price {
+                info.push_str(&format!(" ~ wm ${}", price));
+            }
+        });
+
+    info.parse_message_chain()
+}